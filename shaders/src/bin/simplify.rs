@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use shaders::obj::Obj;
+
+// Genera versiones simplificadas de un OBJ para usarlas como niveles de detalle:
+//   simplify <modelo.obj> <triángulos>... [--output <directorio>]
+// Escribe <modelo>_<triángulos>.obj junto al original o en el directorio indicado, que se crea si falta
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (input, targets, output) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("usage: simplify <model.obj> <triangles>... [--output <directory>]");
+            process::exit(2);
+        }
+    };
+
+    let obj = match Obj::load(&input) {
+        Ok(obj) => obj,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    let triangles: usize = obj.meshes().iter().map(|mesh| mesh.triangle_count()).sum();
+
+    let directory = output.unwrap_or_else(|| input.parent().unwrap_or(Path::new("")).to_path_buf());
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    if let Err(err) = fs::create_dir_all(&directory) {
+        eprintln!("{}: {}", directory.display(), err);
+        process::exit(1);
+    }
+    for target in targets {
+        let simplified = obj.simplify(target);
+        let path = directory.join(format!("{}_{}.obj", stem, target));
+        if let Err(err) = simplified.save(&path) {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        let result: usize = simplified.meshes().iter().map(|mesh| mesh.triangle_count()).sum();
+        println!("{}: {} -> {} triangles", path.display(), triangles, result);
+    }
+}
+
+type Args = (PathBuf, Vec<usize>, Option<PathBuf>);
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut input = None;
+    let mut targets = Vec::new();
+    let mut output = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => {
+                let directory = args.next().ok_or_else(|| format!("{} needs a directory", arg))?;
+                output = Some(PathBuf::from(directory));
+            }
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => targets.push(arg.parse().map_err(|_| format!("invalid triangle count {:?}", arg))?),
+        }
+    }
+    let input = input.ok_or("missing the model to simplify")?;
+    if targets.is_empty() {
+        return Err("missing the triangle counts to generate".to_string());
+    }
+    Ok((input, targets, output))
+}
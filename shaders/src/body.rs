@@ -0,0 +1,98 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// How many simulated days go by per second of animation time
+pub const DAYS_PER_SECOND: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Body {
+  pub name: &'static str,
+  pub axial_tilt: f32,      // radians, tilt of the spin axis away from +Y; past 90° the spin is retrograde
+  pub rotation_period: f32, // days per revolution around the tilted axis
+  pub prime_meridian: f32,  // radians, spin angle at time zero
+}
+
+impl Body {
+  // Angle of the prime meridian around the body's own axis after `days`
+  pub fn spin_angle(&self, days: f32) -> f32 {
+    let turns = days / self.rotation_period + self.prime_meridian / (2.0 * PI);
+    turns.rem_euclid(1.0) * 2.0 * PI
+  }
+
+  // Euler angles for `create_model_matrix`: spin around local Y first, then tilt around Z
+  pub fn rotation(&self, days: f32) -> Vec3 {
+    Vec3::new(0.0, self.spin_angle(days), self.axial_tilt)
+  }
+
+  pub fn for_shader(shader: u8) -> Body {
+    match shader {
+      1 => EARTH,
+      2 => MARS,
+      3 => MERCURY,
+      4 => SATURN,
+      5 => JUPITER,
+      6 => URANUS,
+      7 => SUN,
+      8 => MOON,
+      _ => EARTH,
+    }
+  }
+}
+
+// Tilts and sidereal periods from the IAU rotational elements, prime meridians at J2000
+pub const EARTH: Body = Body {
+  name: "Earth",
+  axial_tilt: 23.44 * PI / 180.0,
+  rotation_period: 0.99727,
+  prime_meridian: 190.147 * PI / 180.0,
+};
+
+pub const MARS: Body = Body {
+  name: "Mars",
+  axial_tilt: 25.19 * PI / 180.0,
+  rotation_period: 1.02596,
+  prime_meridian: 176.630 * PI / 180.0,
+};
+
+pub const MERCURY: Body = Body {
+  name: "Mercury",
+  axial_tilt: 0.034 * PI / 180.0,
+  rotation_period: 58.6462,
+  prime_meridian: 329.5469 * PI / 180.0,
+};
+
+pub const SATURN: Body = Body {
+  name: "Saturn",
+  axial_tilt: 26.73 * PI / 180.0,
+  rotation_period: 0.44401,
+  prime_meridian: 38.90 * PI / 180.0,
+};
+
+pub const JUPITER: Body = Body {
+  name: "Jupiter",
+  axial_tilt: 3.13 * PI / 180.0,
+  rotation_period: 0.41354,
+  prime_meridian: 284.95 * PI / 180.0,
+};
+
+pub const URANUS: Body = Body {
+  name: "Uranus",
+  axial_tilt: 97.77 * PI / 180.0,
+  rotation_period: 0.71833,
+  prime_meridian: 203.81 * PI / 180.0,
+};
+
+pub const SUN: Body = Body {
+  name: "Sun",
+  axial_tilt: 7.25 * PI / 180.0,
+  rotation_period: 25.38,
+  prime_meridian: 84.176 * PI / 180.0,
+};
+
+// Mars' moon is tidally locked: one turn per orbit, see `orbit::MOON`
+pub const MOON: Body = Body {
+  name: "Moon",
+  axial_tilt: 0.0,
+  rotation_period: 0.31891,
+  prime_meridian: 0.0,
+};
@@ -1,42 +1,221 @@
-use nalgebra_glm::{Vec3, rotate_vec3};
+use nalgebra_glm::{Vec2, Vec3, Quat, rotate_vec3, quat_angle_axis, quat_rotate_vec3, quat_normalize};
 use std::f32::consts::PI;
 
+// Highest pitch allowed in free-fly mode, just short of looking straight up or down
+const MAX_FLY_PITCH: f32 = PI / 2.0 - 0.01;
+
+// How quickly the orbit velocity follows its target, per second. Higher values feel less floaty
+const ORBIT_DAMPING: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraMode {
+  Orbit,
+  FreeFly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+  Perspective,
+  // Parallel projection sized so the plane through the center looks the same as in perspective
+  Orthographic,
+}
+
 pub struct Camera {
   pub eye: Vec3,
   pub center: Vec3,
   pub up: Vec3,
-  pub has_changed: bool
+  pub has_changed: bool,
+  pub mode: CameraMode,
+  // Free-fly orientation in radians, derived from the view: yaw around world Y (0 looks towards -Z),
+  // pitch up, roll clockwise
+  pub yaw: f32,
+  pub pitch: f32,
+  pub roll: f32,
+  // Orbit angular velocity in radians per second as (yaw, pitch, roll), and the velocity it eases towards
+  pub orbit_velocity: Vec3,
+  orbit_target: Vec3,
+  // Vertical field of view in radians
+  pub fov: f32,
+  pub projection: Projection,
+  // Off-axis shift of the image in normalized device coordinates; non-zero values give an asymmetric frustum
+  pub lens_shift: Vec2,
 }
 
 impl Camera {
   pub fn new(eye: Vec3, center: Vec3, up: Vec3) -> Self {
-    Camera {
+    let mut camera = Camera {
       eye,
       center,
       up,
       has_changed: true,
+      mode: CameraMode::Orbit,
+      yaw: 0.0,
+      pitch: 0.0,
+      roll: 0.0,
+      orbit_velocity: Vec3::zeros(),
+      orbit_target: Vec3::zeros(),
+      fov: PI / 4.0,
+      projection: Projection::Perspective,
+      lens_shift: Vec2::zeros(),
+    };
+    camera.orthonormalize_up();
+    camera.sync_angles();
+    camera
+  }
+
+  pub fn set_mode(&mut self, mode: CameraMode) {
+    if mode == CameraMode::FreeFly && self.mode != mode {
+      self.sync_angles();
     }
+    // Both modes work from eye, center and up as they are, so switching never changes the view
+    self.mode = mode;
+    self.orbit_velocity = Vec3::zeros();
+    self.orbit_target = Vec3::zeros();
+    self.has_changed = true;
+  }
+
+  pub fn toggle_mode(&mut self) {
+    let mode = match self.mode {
+      CameraMode::Orbit => CameraMode::FreeFly,
+      CameraMode::FreeFly => CameraMode::Orbit,
+    };
+    self.set_mode(mode);
+  }
+
+  // Unit vector the camera is looking along
+  pub fn forward(&self) -> Vec3 {
+    (self.center - self.eye).normalize()
+  }
+
+  // Translates the free-fly camera; `movement` is (right, up, forward) relative to the view
+  pub fn fly(&mut self, movement: Vec3) {
+    let forward = self.forward();
+    let right = forward.cross(&self.up).normalize();
+    let up = right.cross(&forward);
+
+    let offset = right * movement.x + up * movement.y + forward * movement.z;
+    self.eye += offset;
+    self.center += offset;
+    self.has_changed = true;
+  }
+
+  // Turns the free-fly camera from its current view: yaw around world Y, pitch around the level right
+  // axis and roll around the line of sight. Pitch stops at MAX_FLY_PITCH, but a view already past it
+  // (looking straight down a pole) is kept and can only pitch back towards the horizon
+  pub fn look(&mut self, delta_yaw: f32, delta_pitch: f32, delta_roll: f32) {
+    let forward = self.forward();
+    let pitch = forward.y.clamp(-1.0, 1.0).asin();
+    let limit = MAX_FLY_PITCH.max(pitch.abs());
+    let delta_pitch = (pitch + delta_pitch).clamp(-limit, limit) - pitch;
+
+    // At the poles the level right axis is undefined and the view's own right axis takes its place
+    let level_right = forward.cross(&Vec3::y());
+    let right = if level_right.magnitude() > 1e-6 { level_right.normalize() } else { self.basis().0 };
+
+    let rotation = quat_angle_axis(-delta_yaw, &Vec3::y()) * quat_angle_axis(delta_pitch, &right);
+    self.rotate_around_eye(&rotation);
+    self.roll(delta_roll);
+    self.sync_angles();
   }
 
+  // Derives yaw, pitch and roll from the current eye, center and up
+  fn sync_angles(&mut self) {
+    let forward = self.forward();
+    self.yaw = forward.x.atan2(-forward.z);
+    self.pitch = forward.y.clamp(-1.0, 1.0).asin();
+
+    let (level_right, level_up) = level_basis(self.yaw, self.pitch);
+    let up = self.up.normalize();
+    self.roll = up.dot(&level_right).atan2(up.dot(&level_up));
+  }
+
+  // Arcball orbit around the center. Yaw turns around the camera's own up axis and pitch around its
+  // right axis, so the camera can keep going over the poles without flipping
   pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
-    let radius_vector = self.eye - self.center;
-    let radius = radius_vector.magnitude();
+    let (right, up) = self.basis();
+    let rotation = quat_angle_axis(-delta_yaw, &up) * quat_angle_axis(delta_pitch, &right);
+    self.rotate_around_center(&rotation);
+  }
 
-    let current_yaw = radius_vector.z.atan2(radius_vector.x);
+  // Rotates the view around the line of sight; positive angles roll clockwise
+  pub fn roll(&mut self, angle: f32) {
+    let rotation = quat_angle_axis(angle, &self.forward());
+    self.up = quat_rotate_vec3(&rotation, &self.up);
+    self.orthonormalize_up();
+    self.has_changed = true;
+  }
 
-    let radius_xz = (radius_vector.x * radius_vector.x + radius_vector.z * radius_vector.z).sqrt();
-    let current_pitch = (-radius_vector.y).atan2(radius_xz);
+  // Sets the (yaw, pitch, roll) rates in radians per second the orbit eases towards in `update`
+  pub fn set_orbit_velocity(&mut self, velocity: Vec3) {
+    self.orbit_target = velocity;
+  }
+
+  // Advances the inertial orbit by `dt` seconds
+  pub fn update(&mut self, dt: f32) {
+    let blend = 1.0 - (-ORBIT_DAMPING * dt).exp();
+    self.orbit_velocity += (self.orbit_target - self.orbit_velocity) * blend;
+
+    if self.orbit_target == Vec3::zeros() && self.orbit_velocity.magnitude() < 1e-4 {
+      self.orbit_velocity = Vec3::zeros();
+      return;
+    }
+
+    let velocity = self.orbit_velocity * dt;
+    self.orbit(velocity.x, velocity.y);
+    self.roll(velocity.z);
+  }
+
+  // Right and up vectors of the view, orthonormal to the line of sight
+  fn basis(&self) -> (Vec3, Vec3) {
+    let forward = self.forward();
+    let right = forward.cross(&self.up).normalize();
+    (right, right.cross(&forward))
+  }
 
-    let new_yaw = (current_yaw + delta_yaw) % (2.0 * PI);
-    let new_pitch = (current_pitch + delta_pitch).clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
+  fn rotate_around_eye(&mut self, rotation: &Quat) {
+    let rotation = quat_normalize(rotation);
+    self.center = self.eye + quat_rotate_vec3(&rotation, &(self.center - self.eye));
+    self.up = quat_rotate_vec3(&rotation, &self.up);
+    self.orthonormalize_up();
+    self.has_changed = true;
+  }
 
-    let new_eye = self.center + Vec3::new(
-      radius * new_yaw.cos() * new_pitch.cos(),
-      -radius * new_pitch.sin(),
-      radius * new_yaw.sin() * new_pitch.cos()
-    );
+  fn rotate_around_center(&mut self, rotation: &Quat) {
+    let rotation = quat_normalize(rotation);
+    self.eye = self.center + quat_rotate_vec3(&rotation, &(self.eye - self.center));
+    self.up = quat_rotate_vec3(&rotation, &self.up);
+    self.orthonormalize_up();
+    self.has_changed = true;
+  }
+
+  // Keeps `up` unit length and perpendicular to the line of sight so rounding never accumulates
+  fn orthonormalize_up(&mut self) {
+    let (_, up) = self.basis();
+    self.up = up;
+  }
 
-    self.eye = new_eye;
+  // Narrows (factor < 1) or widens the field of view, keeping it between 5° and 120°
+  pub fn zoom_fov(&mut self, factor: f32) {
+    self.fov = (self.fov * factor).clamp(5f32.to_radians(), 120f32.to_radians());
+    self.has_changed = true;
+  }
+
+  pub fn toggle_projection(&mut self) {
+    self.projection = match self.projection {
+      Projection::Perspective => Projection::Orthographic,
+      Projection::Orthographic => Projection::Perspective,
+    };
+    self.has_changed = true;
+  }
+
+  pub fn distance(&self) -> f32 {
+    (self.center - self.eye).magnitude()
+  }
+
+  // Moves eye and center together, keeping the orientation
+  pub fn translate(&mut self, offset: Vec3) {
+    self.eye += offset;
+    self.center += offset;
     self.has_changed = true;
   }
 
@@ -62,4 +241,23 @@ impl Camera {
     self.has_changed = true;
   }
   
-}
\ No newline at end of file
+}
+// Right and up vectors of an unrolled view with the given yaw and pitch
+fn level_basis(yaw: f32, pitch: f32) -> (Vec3, Vec3) {
+  let (sin_yaw, cos_yaw) = yaw.sin_cos();
+  let (sin_pitch, cos_pitch) = pitch.sin_cos();
+  let right = Vec3::new(cos_yaw, 0.0, sin_yaw);
+  let up = Vec3::new(-sin_yaw * sin_pitch, cos_pitch, cos_yaw * sin_pitch);
+  (right, up)
+}
+
+// Initial view used by the viewer and headless renders
+impl Default for Camera {
+  fn default() -> Self {
+    Camera::new(
+      Vec3::new(0.0, 2.0, 10.0),
+      Vec3::new(0.0, 0.0, 0.0),
+      Vec3::new(0.0, 10.0, 0.0)
+    )
+  }
+}
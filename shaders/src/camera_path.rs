@@ -0,0 +1,215 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use nalgebra_glm::Vec3;
+use crate::transition::CameraPose;
+
+// Playback seconds between keyframes added from the viewer. The file can be edited for other timings
+pub const KEYFRAME_SPACING: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+  // Playback time in seconds, counted from the start of the path
+  pub at: f32,
+  pub pose: CameraPose,
+  // Simulation time shown at this keyframe
+  pub time: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+  // Passes through every keyframe
+  CatmullRom,
+  // Uses the keyframes as control points of a single curve: smoother, but only the ends are hit exactly
+  Bezier,
+}
+
+impl Interpolation {
+  pub fn name(self) -> &'static str {
+    match self {
+      Interpolation::CatmullRom => "catmull-rom",
+      Interpolation::Bezier => "bezier",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Interpolation> {
+    [Interpolation::CatmullRom, Interpolation::Bezier].into_iter().find(|interpolation| interpolation.name() == name)
+  }
+}
+
+// Every keyframe channel as a flat array: eye, center, up, fov and simulation time
+type Channels = [f32; 11];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraPath {
+  pub keyframes: Vec<Keyframe>,
+  pub interpolation: Interpolation,
+}
+
+impl CameraPath {
+  pub fn new(interpolation: Interpolation) -> Self {
+    CameraPath { keyframes: Vec::new(), interpolation }
+  }
+
+  // Appends a keyframe `KEYFRAME_SPACING` seconds after the last one
+  pub fn add(&mut self, pose: CameraPose, time: f32) {
+    let at = self.keyframes.last().map_or(0.0, |last| last.at + KEYFRAME_SPACING);
+    self.keyframes.push(Keyframe { at, pose, time });
+  }
+
+  pub fn duration(&self) -> f32 {
+    match (self.keyframes.first(), self.keyframes.last()) {
+      (Some(first), Some(last)) => last.at - first.at,
+      _ => 0.0,
+    }
+  }
+
+  // Camera pose and simulation time `at` seconds into the path, clamped to its ends
+  pub fn sample(&self, at: f32) -> Option<(CameraPose, f32)> {
+    let first = self.keyframes.first()?;
+    let last = self.keyframes.last()?;
+    let at = (first.at + at).clamp(first.at, last.at);
+
+    let channels = match self.interpolation {
+      Interpolation::CatmullRom => self.catmull_rom(at),
+      Interpolation::Bezier => {
+        let progress = if last.at > first.at { (at - first.at) / (last.at - first.at) } else { 0.0 };
+        let points: Vec<Channels> = self.keyframes.iter().map(to_channels).collect();
+        de_casteljau(points, progress)
+      }
+    };
+    Some(from_channels(&channels))
+  }
+
+  fn catmull_rom(&self, at: f32) -> Channels {
+    let keys = &self.keyframes;
+    if keys.len() < 2 {
+      return to_channels(&keys[0]);
+    }
+
+    // The segment that contains `at`; keyframes sharing the same `at` collapse to a jump
+    let segment = keys.windows(2).position(|pair| at <= pair[1].at).unwrap_or(keys.len() - 2);
+
+    let k1 = &keys[segment];
+    let k2 = &keys[segment + 1];
+    let k0 = &keys[segment.saturating_sub(1)];
+    let k3 = &keys[(segment + 2).min(keys.len() - 1)];
+    let span = k2.at - k1.at;
+    let t = if span > 0.0 { (at - k1.at) / span } else { 1.0 };
+
+    let (p0, p1, p2, p3) = (to_channels(k0), to_channels(k1), to_channels(k2), to_channels(k3));
+    let mut result = [0.0; 11];
+    for i in 0..result.len() {
+      result[i] = 0.5 * (
+        2.0 * p1[i]
+          + (p2[i] - p0[i]) * t
+          + (2.0 * p0[i] - 5.0 * p1[i] + 4.0 * p2[i] - p3[i]) * t * t
+          + (3.0 * p1[i] - p0[i] - 3.0 * p2[i] + p3[i]) * t * t * t
+      );
+    }
+    result
+  }
+
+  // One line per keyframe after the interpolation header, with every number separated by spaces
+  pub fn to_text(&self) -> String {
+    let mut text = String::from("# camera path: at time eye.x eye.y eye.z center.x center.y center.z up.x up.y up.z fov\n");
+    text.push_str(&format!("interpolation {}\n", self.interpolation.name()));
+    for keyframe in &self.keyframes {
+      let pose = &keyframe.pose;
+      let values = [
+        keyframe.at, keyframe.time,
+        pose.eye.x, pose.eye.y, pose.eye.z,
+        pose.center.x, pose.center.y, pose.center.z,
+        pose.up.x, pose.up.y, pose.up.z,
+        pose.fov,
+      ];
+      let line: Vec<String> = values.iter().map(f32::to_string).collect();
+      text.push_str(&line.join(" "));
+      text.push('\n');
+    }
+    text
+  }
+
+  pub fn parse(text: &str) -> Result<Self, String> {
+    let mut path = CameraPath::new(Interpolation::CatmullRom);
+    for (index, line) in text.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+      let error = |message: &str| format!("line {}: {}", index + 1, message);
+
+      if let Some(name) = line.strip_prefix("interpolation ") {
+        path.interpolation = Interpolation::from_name(name.trim()).ok_or_else(|| error("unknown interpolation"))?;
+        continue;
+      }
+
+      let values = line.split_whitespace()
+        .map(|value| value.parse::<f32>())
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|_| error("expected numbers"))?;
+      if values.len() != 12 {
+        return Err(error("expected 12 values per keyframe"));
+      }
+      if path.keyframes.last().is_some_and(|last| values[0] < last.at) {
+        return Err(error("keyframes must be in playback order"));
+      }
+
+      path.keyframes.push(Keyframe {
+        at: values[0],
+        time: values[1],
+        pose: CameraPose {
+          eye: Vec3::new(values[2], values[3], values[4]),
+          center: Vec3::new(values[5], values[6], values[7]),
+          up: Vec3::new(values[8], values[9], values[10]),
+          fov: values[11],
+        },
+      });
+    }
+    Ok(path)
+  }
+
+  pub fn save(&self, file: &Path) -> io::Result<()> {
+    fs::write(file, self.to_text())
+  }
+
+  pub fn load(file: &Path) -> io::Result<Self> {
+    let text = fs::read_to_string(file)?;
+    CameraPath::parse(&text).map_err(|message| {
+      io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", file.display(), message))
+    })
+  }
+}
+
+fn to_channels(keyframe: &Keyframe) -> Channels {
+  let pose = &keyframe.pose;
+  [
+    pose.eye.x, pose.eye.y, pose.eye.z,
+    pose.center.x, pose.center.y, pose.center.z,
+    pose.up.x, pose.up.y, pose.up.z,
+    pose.fov, keyframe.time,
+  ]
+}
+
+fn from_channels(channels: &Channels) -> (CameraPose, f32) {
+  let pose = CameraPose {
+    eye: Vec3::new(channels[0], channels[1], channels[2]),
+    center: Vec3::new(channels[3], channels[4], channels[5]),
+    up: Vec3::new(channels[6], channels[7], channels[8]).normalize(),
+    fov: channels[9],
+  };
+  (pose, channels[10])
+}
+
+fn de_casteljau(mut points: Vec<Channels>, t: f32) -> Channels {
+  while points.len() > 1 {
+    for i in 0..points.len() - 1 {
+      let (a, b) = (points[i], points[i + 1]);
+      for (channel, (start, end)) in points[i].iter_mut().zip(a.iter().zip(b.iter())) {
+        *channel = start + (end - start) * t;
+      }
+    }
+    points.pop();
+  }
+  points[0]
+}
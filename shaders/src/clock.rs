@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+const MIN_SPEED: f64 = 1.0 / 64.0;
+const MAX_SPEED: f64 = 65536.0;
+// Slack in seconds when counting whole fixed steps, so a frame that lasts an exact multiple of
+// the step is not one step short because of rounding in the accumulator
+const STEP_TOLERANCE: f64 = 1e-9;
+
+// Simulation time in seconds, decoupled from the wall clock and the frame rate
+pub struct SimClock {
+  time: f64,
+  speed: f64,
+  paused: bool,
+  reversed: bool,
+  fixed_timestep: Option<f64>,
+  accumulator: f64,
+}
+
+impl SimClock {
+  pub fn new() -> Self {
+    SimClock {
+      time: 0.0,
+      speed: 1.0,
+      paused: false,
+      reversed: false,
+      fixed_timestep: None,
+      accumulator: 0.0,
+    }
+  }
+
+  // Advances the clock by a frame that took `real_delta` of wall time
+  pub fn tick(&mut self, real_delta: Duration) {
+    if self.paused {
+      return;
+    }
+
+    let direction = if self.reversed { -1.0 } else { 1.0 };
+    let real_delta = real_delta.as_secs_f64();
+
+    match self.fixed_timestep {
+      Some(step) => {
+        // Only whole steps are simulated, the remainder carries over to the next frame
+        self.accumulator += real_delta;
+        while self.accumulator >= step - STEP_TOLERANCE {
+          self.time += step * self.speed * direction;
+          self.accumulator -= step;
+        }
+      }
+      None => self.time += real_delta * self.speed * direction,
+    }
+  }
+
+  pub fn time(&self) -> f32 {
+    self.time as f32
+  }
+
+  pub fn jump_to(&mut self, time: f64) {
+    self.time = time;
+    self.accumulator = 0.0;
+  }
+
+  pub fn jump_by(&mut self, delta: f64) {
+    self.jump_to(self.time + delta);
+  }
+
+  pub fn toggle_pause(&mut self) {
+    self.paused = !self.paused;
+  }
+
+  pub fn is_paused(&self) -> bool {
+    self.paused
+  }
+
+  pub fn toggle_reverse(&mut self) {
+    self.reversed = !self.reversed;
+  }
+
+  pub fn is_reversed(&self) -> bool {
+    self.reversed
+  }
+
+  pub fn speed(&self) -> f64 {
+    self.speed
+  }
+
+  pub fn set_speed(&mut self, speed: f64) {
+    self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+  }
+
+  pub fn speed_up(&mut self) {
+    self.set_speed(self.speed * 2.0);
+  }
+
+  pub fn slow_down(&mut self) {
+    self.set_speed(self.speed / 2.0);
+  }
+
+  pub fn fixed_timestep(&self) -> Option<f64> {
+    self.fixed_timestep
+  }
+
+  pub fn set_fixed_timestep(&mut self, step: Option<f64>) {
+    self.fixed_timestep = step.filter(|step| *step > 0.0);
+    self.accumulator = 0.0;
+  }
+}
+
+impl Default for SimClock {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -36,7 +36,7 @@ impl Color {
   }
 
   // Function to return the color as a hex value
-  pub fn to_hex(&self) -> u32 {
+  pub fn to_hex(self) -> u32 {
     ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
   }
 
@@ -76,9 +76,9 @@ impl Color {
   }
 
   pub fn blend_subtract(&self, blend: &Color) -> Color {
-    let r = (self.r as i16 - blend.r as i16).max(0).min(255) as u8;
-    let g = (self.g as i16 - blend.g as i16).max(0).min(255) as u8;
-    let b = (self.b as i16 - blend.b as i16).max(0).min(255) as u8;
+    let r = (self.r as i16 - blend.r as i16).clamp(0, 255) as u8;
+    let g = (self.g as i16 - blend.g as i16).clamp(0, 255) as u8;
+    let b = (self.b as i16 - blend.b as i16).clamp(0, 255) as u8;
 
     Color::new(r, g, b)
   }
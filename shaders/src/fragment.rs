@@ -8,10 +8,15 @@ pub struct Fragment {
     pub normal: Vec3,
     pub intensity: f32,
     pub vertex_position: Vec3,
+    pub world_position: Vec3,
     pub uv: Option<Vec2>,
+    // Base tangente en coordenadas de mundo, interpolada sin normalizar
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
 }
 
 impl Fragment {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         position: Vec2,
         color: Color,
@@ -19,7 +24,10 @@ impl Fragment {
         normal: Vec3,
         intensity: f32,
         vertex_position: Vec3,
-        uv: Option<Vec2>
+        world_position: Vec3,
+        uv: Option<Vec2>,
+        tangent: Vec3,
+        bitangent: Vec3,
     ) -> Self {  
         Fragment {
             position,
@@ -28,7 +36,10 @@ impl Fragment {
             normal,
             intensity,
             vertex_position,
-            uv
+            world_position,
+            uv,
+            tangent,
+            bitangent,
         }
     }
 }
\ No newline at end of file
@@ -23,6 +23,15 @@ impl Framebuffer {
         }
     }
 
+    // Cambia la resolución; el contenido se descarta hasta el siguiente `clear`
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.buffer = vec![self.background_color; width * height];
+        self.zbuffer = vec![f32::INFINITY; width * height];
+        self.emissive_buffer = vec![0; width * height];
+    }
+
     pub fn clear(&mut self) {
         for pixel in self.buffer.iter_mut() {
             *pixel = self.background_color;
@@ -35,6 +44,11 @@ impl Framebuffer {
         }
     }
 
+    // Indica si un fragmento en (x, y) quedaría delante de lo ya dibujado, sin escribir nada
+    pub fn depth_test(&self, x: usize, y: usize, depth: f32) -> bool {
+        x < self.width && y < self.height && self.zbuffer[y * self.width + x] > depth
+    }
+
     pub fn point(&mut self, x: usize, y: usize, depth: f32, emit: u32) {
         if x < self.width && y < self.height {
             let index = y * self.width + x;
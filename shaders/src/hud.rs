@@ -0,0 +1,90 @@
+use std::time::Duration;
+use crate::body::DAYS_PER_SECOND;
+use crate::camera::{Camera, CameraMode, Projection};
+use crate::framebuffer::Framebuffer;
+use crate::profiler::RenderStats;
+use crate::text::{draw_text, shade_rect, text_height, text_width};
+
+const TEXT_COLOR: u32 = 0xE8E8F0;
+const PANEL_COLOR: u32 = 0x000000;
+const PANEL_OPACITY: f32 = 0.55;
+const MARGIN: usize = 8;
+const PADDING: usize = 6;
+
+// Tiempo de cada parte del último cuadro
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimes {
+    pub update: Duration,  // entrada, cámara y reloj
+    pub render: Duration,  // escena y efectos
+    pub upscale: Duration, // escalado a la ventana
+    pub present: Duration, // envío del cuadro anterior a la ventana
+}
+
+impl FrameTimes {
+    pub fn total(&self) -> Duration {
+        self.update + self.render + self.upscale + self.present
+    }
+}
+
+// Todo lo que muestra el HUD
+pub struct HudInfo<'a> {
+    pub body: &'a str,
+    pub fps: f32,
+    pub frame: FrameTimes,
+    pub pipeline: RenderStats, // suma de todos los objetos del cuadro
+    pub profiling: bool,
+    pub time: f32,
+    pub clock_status: String,
+    pub render_size: (usize, usize),
+    pub resolution_status: String,
+    pub camera: &'a Camera,
+    pub effects: Vec<&'static str>,
+}
+
+pub fn hud_text(info: &HudInfo) -> String {
+    let ms = |duration: Duration| duration.as_secs_f32() * 1000.0;
+    let camera = info.camera;
+    let mode = match camera.mode {
+        CameraMode::Orbit => "orbit",
+        CameraMode::FreeFly => "free-fly",
+    };
+    let projection = match camera.projection {
+        Projection::Perspective => "perspective",
+        Projection::Orthographic => "orthographic",
+    };
+    let effects = if info.effects.is_empty() { "none".to_string() } else { info.effects.join(", ") };
+
+    [
+        info.body.to_string(),
+        format!("{:.1} fps  {:.1} ms", info.fps, ms(info.frame.total())),
+        format!(
+            "update {:.1}  render {:.1}  upscale {:.1}  present {:.1} ms",
+            ms(info.frame.update), ms(info.frame.render), ms(info.frame.upscale), ms(info.frame.present)
+        ),
+        format!(
+            "vertex {:.1}  raster {:.1}  fragment {:.1} ms",
+            ms(info.pipeline.vertex), ms(info.pipeline.raster), ms(info.pipeline.fragment)
+        ),
+        format!(
+            "{} tris  {} frags  {} shaded  {} z-rejected",
+            info.pipeline.triangles, info.pipeline.fragments, info.pipeline.shaded, info.pipeline.depth_rejected
+        ),
+        format!("time {:.1} s ({:.2} days)  {}", info.time, info.time * DAYS_PER_SECOND, info.clock_status),
+        format!("{}  {}x{}", info.resolution_status, info.render_size.0, info.render_size.1),
+        format!("camera {:.2} {:.2} {:.2}", camera.eye.x, camera.eye.y, camera.eye.z),
+        format!("{} {} fov {:.0}", mode, projection, camera.fov.to_degrees()),
+        format!("effects: {}", effects),
+        if info.profiling { "F2 stops profiling" } else { "F2 profiles, H hides this panel" }.to_string(),
+    ].join("\n")
+}
+
+// Dibuja el panel en la esquina superior izquierda
+pub fn draw_hud(framebuffer: &mut Framebuffer, info: &HudInfo) {
+    let text = hud_text(info);
+    let scale = 1;
+    let width = text_width(&text, scale) + 2 * PADDING;
+    let height = text_height(&text, scale) + 2 * PADDING;
+
+    shade_rect(framebuffer, MARGIN, MARGIN, width, height, PANEL_COLOR, PANEL_OPACITY);
+    draw_text(framebuffer, MARGIN + PADDING, MARGIN + PADDING, &text, TEXT_COLOR, scale);
+}
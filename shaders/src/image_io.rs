@@ -0,0 +1,76 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+// Guarda un buffer 0xRRGGBB como PNG RGB de 8 bits
+pub fn save_png(path: &Path, width: usize, height: usize, buffer: &[u32]) -> io::Result<()> {
+    save_png_with_metadata(path, width, height, buffer, &[])
+}
+
+// Igual que `save_png`, añadiendo pares clave/valor como bloques tEXt
+pub fn save_png_with_metadata(path: &Path, width: usize, height: usize, buffer: &[u32], metadata: &[(&str, String)]) -> io::Result<()> {
+    write_png(path, width, height, png::ColorType::Rgb, png::BitDepth::Eight, &to_rgb_bytes(buffer), metadata)
+}
+
+// Guarda valores de 0.0 a 1.0 como PNG en escala de grises de 16 bits
+pub fn save_grayscale_png(path: &Path, width: usize, height: usize, values: &[f32], metadata: &[(&str, String)]) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(values.len() * 2);
+    for &value in values {
+        let level = (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16;
+        bytes.extend_from_slice(&level.to_be_bytes());
+    }
+    write_png(path, width, height, png::ColorType::Grayscale, png::BitDepth::Sixteen, &bytes, metadata)
+}
+
+fn write_png(
+    path: &Path,
+    width: usize,
+    height: usize,
+    color: png::ColorType,
+    depth: png::BitDepth,
+    data: &[u8],
+    metadata: &[(&str, String)],
+) -> io::Result<()> {
+    let file = File::create(path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+    for (key, value) in metadata {
+        encoder.add_text_chunk(key.to_string(), value.clone())?;
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(data)?;
+    Ok(())
+}
+
+// Carga un PNG de 8 bits (RGB o RGBA) como buffer 0xRRGGBB
+pub fn load_png(path: &Path) -> io::Result<(usize, usize, Vec<u32>)> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let mut reader = decoder.read_info()?;
+    let mut bytes = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut bytes)?;
+
+    let channels = match (info.color_type, info.bit_depth) {
+        (png::ColorType::Rgb, png::BitDepth::Eight) => 3,
+        (png::ColorType::Rgba, png::BitDepth::Eight) => 4,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "only 8-bit RGB and RGBA images are supported")),
+    };
+
+    let buffer = bytes[..info.buffer_size()]
+        .chunks(channels)
+        .map(|p| ((p[0] as u32) << 16) | ((p[1] as u32) << 8) | p[2] as u32)
+        .collect();
+
+    Ok((info.width as usize, info.height as usize, buffer))
+}
+
+pub fn to_rgb_bytes(buffer: &[u32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(buffer.len() * 3);
+    for &pixel in buffer {
+        bytes.push((pixel >> 16) as u8);
+        bytes.push((pixel >> 8) as u8);
+        bytes.push(pixel as u8);
+    }
+    bytes
+}
@@ -0,0 +1,67 @@
+use nalgebra_glm::Mat4;
+use fastnoise_lite::FastNoiseLite;
+use noise::{create_cloud_noise, create_jupiter_band_noise, create_noise};
+use relief::Relief;
+
+pub mod framebuffer;
+pub mod triangle;
+pub mod vertex;
+pub mod obj;
+pub mod material;
+pub mod mesh;
+pub mod primitives;
+pub mod simplify;
+pub mod color;
+pub mod fragment;
+pub mod shaders;
+pub mod camera;
+pub mod body;
+pub mod orbit;
+pub mod clock;
+pub mod noise;
+pub mod relief;
+pub mod render;
+pub mod postprocess;
+pub mod scene;
+pub mod image_io;
+pub mod record;
+pub mod screenshot;
+pub mod transition;
+pub mod camera_path;
+pub mod resolution;
+pub mod text;
+pub mod hud;
+pub mod profiler;
+
+pub struct Uniforms {
+    pub model_matrix: Mat4,
+    pub view_matrix: Mat4,
+    pub projection_matrix: Mat4,
+    pub viewport_matrix: Mat4,
+    pub time: f32,
+    pub noise: FastNoiseLite,
+    pub cloud_noise: FastNoiseLite,
+    pub band_noise: FastNoiseLite,
+    pub current_shader: u8,
+    // Relieve del cuerpo que se dibuja, aplicado en el vertex shader
+    pub relief: Option<Relief>,
+}
+
+impl Uniforms {
+    // Uniforms para dibujar con el shader `shader`: su ruido, su relieve y la matriz de modelo dada.
+    // Vista, proyección y viewport quedan en la identidad hasta que se ajustan a la cámara
+    pub fn for_shader(shader: u8, model_matrix: Mat4) -> Self {
+        Uniforms {
+            model_matrix,
+            view_matrix: Mat4::identity(),
+            projection_matrix: Mat4::identity(),
+            viewport_matrix: Mat4::identity(),
+            time: 0.0,
+            noise: create_noise(shader),
+            cloud_noise: create_cloud_noise(),
+            band_noise: create_jupiter_band_noise(),
+            current_shader: shader,
+            relief: Relief::for_shader(shader),
+        }
+    }
+}
@@ -1,509 +1,566 @@
-use nalgebra_glm::{Vec3, Mat4, look_at, perspective};
-use minifb::{Key, Window, WindowOptions};
-use std::{f32::consts::PI, time::Instant};
-
-mod framebuffer;
-mod triangle;
-mod vertex;
-mod obj;
-mod color;
-mod fragment;
-mod shaders;
-mod camera;
-
-use framebuffer::Framebuffer;
-use vertex::Vertex;
-use obj::Obj;
-use camera::Camera;
-use triangle::triangle;
-use shaders::{vertex_shader, fragment_shader};
-use fastnoise_lite::{FastNoiseLite, NoiseType, FractalType};
-
-
-pub struct Uniforms {
-    model_matrix: Mat4,
-    view_matrix: Mat4,
-    projection_matrix: Mat4,
-    viewport_matrix: Mat4,
-    time: u32,
-    noise: FastNoiseLite,
-    cloud_noise: FastNoiseLite, 
-    band_noise: FastNoiseLite, 
-    current_shader: u8, 
-}
-
-fn create_noise(current_shader: u8) -> FastNoiseLite {
-    match current_shader {
-        1 => create_earth_noise(),
-        2 => create_mars_noise(),
-        3 => create_mercury_noise(),
-        4 => FastNoiseLite::new(),
-        5 => create_jupiter_noise(),
-        6 => create_urano_noise(), 
-        8 => create_moon_noise(),
-        9 => FastNoiseLite::new(),
-        _ => create_earth_noise(),  
-    }
-}
-
-fn create_earth_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1337);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2S));
-    noise.set_fractal_type(Some(FractalType::Ridged));
-    noise.set_fractal_octaves(Some(5)); // Octavas para mayor detalle
-    noise.set_fractal_lacunarity(Some(3.0)); // Lacunaridad para escalado de frecuencia
-    noise.set_fractal_gain(Some(0.5)); // Ganancia para el escalado de amplitud
-    noise.set_frequency(Some(0.5)); 
-
-    noise
-}
-
-fn create_cloud_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(40);  
-    noise.set_noise_type(Some(NoiseType::Perlin)); 
-    noise.set_fractal_type(Some(FractalType::FBm));
-    noise.set_fractal_octaves(Some(2));  // Menos octavas para menos detalles
-    noise.set_fractal_lacunarity(Some(3.0));
-    noise.set_fractal_gain(Some(0.5));
-    noise.set_frequency(Some(0.01));  // Baja frecuencia para nubes grandes y suaves
-    noise
-}
-
-fn create_mars_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1234);
-    noise.set_noise_type(Some(NoiseType::Perlin));
-    noise.set_fractal_type(Some(FractalType::Ridged));
-    noise.set_fractal_octaves(Some(4));
-    noise.set_fractal_lacunarity(Some(2.0));
-    noise.set_fractal_gain(Some(0.5));
-    noise.set_frequency(Some(1.5)); 
-    noise
-}
-
-fn create_moon_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(4321);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-    noise.set_fractal_type(Some(FractalType::PingPong));
-    noise.set_fractal_octaves(Some(2));
-    noise.set_fractal_lacunarity(Some(2.0));
-    noise.set_fractal_gain(Some(0.5));
-    noise.set_frequency(Some(3.0));  
-    noise
-}
-
-fn create_mercury_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(4321);
-    noise.set_noise_type(Some(NoiseType::Perlin));
-    noise.set_fractal_type(Some(FractalType::PingPong));
-    noise.set_fractal_octaves(Some(5));
-    noise.set_fractal_lacunarity(Some(2.0));
-    noise.set_fractal_gain(Some(1.0));
-    noise.set_frequency(Some(5.0));  
-    noise
-}
-
-fn create_jupiter_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(5678); // Puedes elegir cualquier semilla
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2)); // OpenSimplex2 produce un ruido más suave
-    noise.set_fractal_type(Some(FractalType::DomainWarpProgressive)); // Añade complejidad fractal
-    noise.set_fractal_octaves(Some(6)); // Más octavas para más detalle
-    noise.set_fractal_lacunarity(Some(2.0)); // Lacunaridad estándar
-    noise.set_fractal_gain(Some(0.5)); // Ganancia menor para detalles finos
-    noise.set_frequency(Some(2.0)); // Ajusta la escala del ruido
-    noise
-}
-
-fn create_jupiter_band_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(7890); // Nueva semilla
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-    noise.set_frequency(Some(1.0));
-    noise.set_fractal_type(Some(FractalType::FBm));
-    noise
-}
-
-fn create_urano_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(2021);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-    noise.set_fractal_type(Some(FractalType::Ridged));
-    noise.set_fractal_octaves(Some(4));
-    noise.set_fractal_lacunarity(Some(2.0));
-    noise.set_fractal_gain(Some(0.4));
-    noise.set_frequency(Some(0.2));
-    noise
-}
-
-fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
-    let (sin_x, cos_x) = rotation.x.sin_cos();
-    let (sin_y, cos_y) = rotation.y.sin_cos();
-    let (sin_z, cos_z) = rotation.z.sin_cos();
-
-    let rotation_matrix_x = Mat4::new(
-        1.0,  0.0,    0.0,   0.0,
-        0.0,  cos_x, -sin_x, 0.0,
-        0.0,  sin_x,  cos_x, 0.0,
-        0.0,  0.0,    0.0,   1.0,
-    );
-
-    let rotation_matrix_y = Mat4::new(
-        cos_y,  0.0,  sin_y, 0.0,
-        0.0,    1.0,  0.0,   0.0,
-        -sin_y, 0.0,  cos_y, 0.0,
-        0.0,    0.0,  0.0,   1.0,
-    );
-
-    let rotation_matrix_z = Mat4::new(
-        cos_z, -sin_z, 0.0, 0.0,
-        sin_z,  cos_z, 0.0, 0.0,
-        0.0,    0.0,  1.0, 0.0,
-        0.0,    0.0,  0.0, 1.0,
-    );
-
-    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
-
-    let transform_matrix = Mat4::new(
-        scale, 0.0,   0.0,   translation.x,
-        0.0,   scale, 0.0,   translation.y,
-        0.0,   0.0,   scale, translation.z,
-        0.0,   0.0,   0.0,   1.0,
-    );
-
-    transform_matrix * rotation_matrix
-}
-
-
-fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
-    look_at(&eye, &center, &up)
-}
-
-fn create_perspective_matrix(window_width: f32, window_height: f32) -> Mat4 {
-    let fov = 45.0 * PI / 180.0;
-    let aspect_ratio = window_width / window_height;
-    let near = 0.1;
-    let far = 1000.0;
-
-    perspective(fov, aspect_ratio, near, far)
-}
-
-fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
-    Mat4::new(
-        width / 2.0, 0.0, 0.0, width / 2.0,
-        0.0, -height / 2.0, 0.0, height / 2.0,
-        0.0, 0.0, 1.0, 0.0,
-        0.0, 0.0, 0.0, 1.0
-    )
-}
-
-fn gaussian_blur(buffer: &mut [u32], width: usize, height: usize, kernel_size: usize, sigma: f32) {
-    let gaussian_kernel = create_gaussian_kernel(kernel_size, sigma);
-    let kernel_sum: f32 = gaussian_kernel.iter().map(|&x| x as f32).sum();
-
-    // Aplicar horizontalmente
-    for y in 0..height {
-        let mut temp_row = vec![0u32; width];
-        for x in 0..width {
-            let mut filtered_pixel = 0f32;
-            for k in 0..gaussian_kernel.len() {
-                let sample_x = x as i32 + k as i32 - (gaussian_kernel.len() / 2) as i32;
-                if sample_x >= 0 && sample_x < width as i32 {
-                    filtered_pixel += buffer[sample_x as usize + y * width] as f32 * gaussian_kernel[k] as f32;
-                }
-            }
-            temp_row[x] = (filtered_pixel / kernel_sum).round() as u32;
-        }
-        buffer[y * width..(y + 1) * width].copy_from_slice(&temp_row);
-    }
-
-    // Aplicar verticalmente
-    for x in 0..width {
-        let mut temp_col = vec![0u32; height];
-        for y in 0..height {
-            let mut filtered_pixel = 0f32;
-            for k in 0..gaussian_kernel.len() {
-                let sample_y = y as i32 + k as i32 - (gaussian_kernel.len() / 2) as i32;
-                if sample_y >= 0 && sample_y < height as i32 {
-                    filtered_pixel += buffer[x + sample_y as usize * width] as f32 * gaussian_kernel[k] as f32;
-                }
-            }
-            temp_col[y] = (filtered_pixel / kernel_sum).round() as u32;
-        }
-        for y in 0..height {
-            buffer[x + y * width] = temp_col[y];
-        }
-    }
-}
-
-// Crear un kernel Gaussiano dinámicamente
-fn create_gaussian_kernel(size: usize, sigma: f32) -> Vec<u32> {
-    let mut kernel = vec![0u32; size];
-    let mean = (size as f32 - 1.0) / 2.0;
-    let coefficient = 1.0 / (2.0 * std::f32::consts::PI * sigma * sigma).sqrt();
-
-    for x in 0..size {
-        let exp_numerator = -((x as f32 - mean) * (x as f32 - mean)) / (2.0 * sigma * sigma);
-        let exp_value = (-exp_numerator).exp();
-        kernel[x] = (coefficient * exp_value * 255.0) as u32;
-    }
-
-    kernel
-}
-
-fn apply_bloom(original: &mut [u32], bloom: &[u32], width: usize, height: usize) {
-    for i in 0..original.len() {
-        let original_color = original[i];
-        let bloom_intensity = bloom[i];
-        if bloom_intensity > 0 {
-            original[i] = blend_bloom(original_color, bloom_intensity);
-        }
-    }
-}
-
-fn blend_bloom(base_color: u32, bloom_intensity: u32) -> u32 {
-    // Factores para el tonemapping y la mezcla de bloom
-    let bloom_strength = 0.8;  // Ajusta esto para controlar la fuerza del efecto de bloom
-    let max_bloom_effect = 1.2;  // Este valor limita cuánto puede influir el bloom
-
-    let r = ((base_color >> 16) & 0xFF) as f32;
-    let g = ((base_color >> 8) & 0xFF) as f32;
-    let b = (base_color & 0xFF) as f32;
-    let bloom = bloom_intensity as f32 * bloom_strength;
+use nalgebra_glm::{Vec2, Vec3};
+use minifb::{Key, MouseButton, MouseMode, Window, WindowOptions};
+use std::{f32::consts::PI, path::Path, time::{Duration, Instant}};
+
+use shaders::framebuffer::Framebuffer;
+use shaders::hud::{draw_hud, FrameTimes, HudInfo};
+use shaders::postprocess::{resample, resample_bilinear};
+use shaders::profiler::Profiler;
+use shaders::resolution::DynamicResolution;
+use shaders::camera::{Camera, CameraMode};
+use shaders::body::Body;
+use shaders::camera_path::{CameraPath, Interpolation};
+use shaders::clock::SimClock;
+use shaders::scene::{LodState, PlacedBody, Scene, BACKGROUND_COLOR};
+use shaders::record::{record, RecordSettings};
+use shaders::render::screen_ray;
+use shaders::screenshot;
+use shaders::transition::{CameraPose, CameraTransition, Easing};
 
-    // Calcular nueva intensidad de color con clamping para evitar saturación
-    let new_r = ((r + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
-    let new_g = ((g + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
-    let new_b = ((b + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
-
-    // Recomponer el color
-    (new_r << 16) | (new_g << 8) | new_b
-}
-
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], time: u32) {
-    // Vertex Shader Stage
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
-        transformed_vertices.push(transformed);
-    }
-
-    // Primitive Assembly Stage
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match RecordSettings::from_args(&args) {
+        Ok(Some(settings)) => return run_recording(&settings),
+        Ok(None) => {}
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
         }
     }
 
-    // Rasterization Stage
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
-    }
-
-    // Fragment Processing Stage
-    // Fragment Processing Stage
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
-        if x < framebuffer.width && y < framebuffer.height {
-            // Apply fragment shader
-            let (shaded_color, emission) = fragment_shader(&fragment, &uniforms, time);
-            let color = shaded_color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth, emission);  // Asegúrate de que `point` acepte y maneje `emission`
+    let options = match ViewerOptions::from_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
         }
+    };
+    let (transition_seconds, easing) = (options.transition_seconds, options.easing);
+    let mut resolution = DynamicResolution::new(options.target_fps, options.min_scale, options.max_scale);
+    if let Some(scale) = options.render_scale {
+        resolution.set_enabled(false);
+        resolution.set_scale(scale);
     }
-}
 
-fn main() {
     let window_width = 680;
     let window_height = 800;
-    let framebuffer_width = 680;
-    let framebuffer_height = 800;
 
+    let (framebuffer_width, framebuffer_height) = render_size(window_width, window_height, resolution.scale());
     let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
     let mut window = Window::new(
-        "Rust Graphics - Planet Shader - Press 1-7 to switch",
+        "Rust Graphics - Planet Shader - Press 0-7 to switch",
         window_width,
         window_height,
-        WindowOptions::default(),
+        WindowOptions {
+            resize: true,
+            ..WindowOptions::default()
+        },
     )
         .unwrap();
 
     window.set_position(500, 500);
     window.update();
 
-    framebuffer.set_background_color(0x333355);
-
-    // model position
-    let translation = Vec3::new(0.0, 0.0, 0.0);
-    let rotation = Vec3::new(0.0, 0.0, 0.0);
-    let scale = 1.0f32;
+    framebuffer.set_background_color(BACKGROUND_COLOR);
 
     // camera parameters
-    let mut camera = Camera::new(
-        Vec3::new(0.0, 2.0, 10.0),
-        Vec3::new(0.0, 0.0, 0.0),
-        Vec3::new(0.0, 10.0, 0.0)
-    );
-
-    let obj = Obj::load("assets/models/sphere.obj").expect("Failed to load obj");
-    let moon = Obj::load("assets/models/moon.obj").expect("Failed to load obj");
-    let ring_obj = Obj::load("assets/models/ring.obj").expect("Failed to load ring model");
+    let mut camera = Camera::default();
 
-    let vertex_arrays = obj.get_vertex_array(); 
-    let moon_vertex_array = moon.get_vertex_array();
-    let ring_vertex_array = ring_obj.get_vertex_array();
+    let scene = load_scene();
+    // Niveles de detalle del cuadro anterior, para que no parpadeen cerca de un umbral
+    let mut lod_state = LodState::new();
 
     let mut last_frame_time = Instant::now();
-    let mut time = 0;
-
-    // Lunas de los planetas rocosos
-    let moon_scale = 0.5; // Escala de la luna respecto al planeta
-    let moon_distance = 2.5; // Distancia de la luna al planeta
-    let moon_orbit_speed = 0.001; // Velocidad orbital de la luna
-
-    let projection_matrix = create_perspective_matrix(window_width as f32, window_height as f32);
-    let viewport_matrix = create_viewport_matrix(framebuffer_width as f32, framebuffer_height as f32);
-    let mut uniforms = Uniforms { 
-        model_matrix: Mat4::identity(), 
-        view_matrix: Mat4::identity(), 
-        projection_matrix, 
-        viewport_matrix, 
-        time: 0, 
-        noise: create_noise(1),
-        cloud_noise: create_cloud_noise(),
-        band_noise: create_jupiter_band_noise(), 
-        current_shader: 1,
-    };
+    let mut clock = SimClock::new();
 
     let mut current_planet = 1; 
+    let mut mouse = MouseState::default();
+    let mut transition: Option<CameraTransition> = None;
+
+    // Recorrido de cámara para demos y el tiempo de reproducción, si se está reproduciendo
+    let camera_path_file = Path::new("camera_path.txt");
+    let mut camera_path = CameraPath::new(Interpolation::CatmullRom);
+    let mut playback: Option<f32> = None;
+
+    let mut title = String::new();
+
+    // Imagen final al tamaño de la ventana, con el HUD encima
+    let mut display = Framebuffer::new(window_width, window_height);
+    let mut show_hud = true;
+    let mut frame_times = FrameTimes::default();
+    let mut smoothed_frame_time = 0.0f32;
+
+    // Mientras se captura con F2 se guardan los perfiles de todos los cuadros
+    let mut capture: Option<Profiler> = None;
 
     while window.is_open() && !window.is_key_down(Key::Escape) {
 
         let delta_time = last_frame_time.elapsed();
         last_frame_time = Instant::now();
-        time += delta_time.as_millis() as u32;
+        let frame_start = last_frame_time;
 
         let keys = window.get_keys_pressed(minifb::KeyRepeat::No);
+        let mut planet_selected = false;
+        let mut screenshot_requested = false;
         for key in keys {
             match key {
+                // Controles del reloj de simulación
+                Key::Space => {
+                    clock.toggle_pause();
+                }
+                Key::Equal => {
+                    clock.speed_up();
+                }
+                Key::Minus => {
+                    clock.slow_down();
+                }
+                Key::R => {
+                    clock.toggle_reverse();
+                }
+                Key::Home => {
+                    clock.jump_to(0.0);
+                }
+                Key::Comma => {
+                    clock.jump_by(-clock.speed());
+                }
+                Key::Period => {
+                    clock.jump_by(clock.speed());
+                }
+                Key::T => {
+                    let step = if clock.fixed_timestep().is_some() { None } else { Some(1.0 / 60.0) };
+                    clock.set_fixed_timestep(step);
+                }
+                Key::C => {
+                    camera.toggle_mode();
+                }
+                Key::O => {
+                    camera.toggle_projection();
+                }
+                Key::V => {
+                    camera.lens_shift = Vec2::new(0.0, 0.0);
+                }
+                // Escala interna de renderizado respecto a la ventana. Elegirla a mano desactiva el
+                // ajuste automático; F lo vuelve a activar o desactivar
+                Key::PageUp => {
+                    resolution.set_enabled(false);
+                    resolution.set_scale((resolution.scale() + 0.25).min(MAX_RENDER_SCALE));
+                }
+                Key::PageDown => {
+                    resolution.set_enabled(false);
+                    resolution.set_scale((resolution.scale() - 0.25).max(MIN_RENDER_SCALE));
+                }
+                Key::F => {
+                    resolution.set_enabled(!resolution.is_enabled());
+                }
+                Key::H => {
+                    show_hud = !show_hud;
+                }
+                Key::F12 => {
+                    screenshot_requested = true;
+                }
+                Key::F2 => match capture.take() {
+                    Some(profiler) => save_profile(&profiler),
+                    None => {
+                        capture = Some(Profiler::new());
+                        println!("Profiling started, press F2 again to stop");
+                    }
+                },
+                // Recorrido de cámara: K añade la vista actual, P reproduce o detiene, B cambia la
+                // interpolación, Backspace lo borra, F5 lo guarda y F9 lo carga
+                Key::K => {
+                    camera_path.add(CameraPose::of(&camera), clock.time());
+                    println!("Keyframe {} added", camera_path.keyframes.len());
+                }
+                Key::P => {
+                    if playback.is_some() {
+                        playback = None;
+                    } else if camera_path.keyframes.is_empty() {
+                        println!("The camera path is empty, press K to add keyframes");
+                    } else {
+                        playback = Some(0.0);
+                        transition = None;
+                        camera.set_mode(CameraMode::Orbit);
+                    }
+                }
+                Key::B => {
+                    camera_path.interpolation = match camera_path.interpolation {
+                        Interpolation::CatmullRom => Interpolation::Bezier,
+                        Interpolation::Bezier => Interpolation::CatmullRom,
+                    };
+                    println!("Camera path interpolation: {}", camera_path.interpolation.name());
+                }
+                Key::Backspace => {
+                    camera_path.keyframes.clear();
+                    playback = None;
+                }
+                Key::F5 => match camera_path.save(camera_path_file) {
+                    Ok(()) => println!("Saved {} keyframes to {}", camera_path.keyframes.len(), camera_path_file.display()),
+                    Err(err) => eprintln!("Saving the camera path failed: {}", err),
+                },
+                Key::F9 => match CameraPath::load(camera_path_file) {
+                    Ok(path) => {
+                        println!("Loaded {} keyframes from {}", path.keyframes.len(), camera_path_file.display());
+                        camera_path = path;
+                    }
+                    Err(err) => eprintln!("Loading the camera path failed: {}", err),
+                },
+                Key::Key0 => {
+                    current_planet = 0;
+                    planet_selected = true;
+                }
                 Key::Key1 => {
                     current_planet = 1;
+                    planet_selected = true;
                 }
                 Key::Key2 => {
                     current_planet = 2;
+                    planet_selected = true;
                 }
                 Key::Key3 => {
                     current_planet = 3;
+                    planet_selected = true;
                 }
                 Key::Key4 => {
                     current_planet = 4;
+                    planet_selected = true;
                 }
                 Key::Key5 => {
                     current_planet = 5;
+                    planet_selected = true;
                 }
                 Key::Key6 => {
                     current_planet = 6;
+                    planet_selected = true;
                 }
                 Key::Key7 => {
                     current_planet = 7;
+                    planet_selected = true;
                 }
                 _ => {}
             }
         }
+        let name = if current_planet == 0 { "Solar System" } else { Body::for_shader(current_planet).name };
+        let new_title = format!(
+            "Rust Graphics - {} - {} - {} - Press 0-7 to switch",
+            name, clock_status(&clock), resolution_status(&resolution)
+        );
+        if new_title != title {
+            window.set_title(&new_title);
+            title = new_title;
+        }
+        clock.tick(delta_time);
+
+        // El framebuffer sigue el tamaño de la ventana multiplicado por la escala de renderizado
+        let (window_width, window_height) = window.get_size();
+        let (window_width, window_height) = (window_width.max(1), window_height.max(1));
+        let (render_width, render_height) = render_size(window_width, window_height, resolution.scale());
+        if (render_width, render_height) != (framebuffer.width, framebuffer.height) {
+            framebuffer.resize(render_width, render_height);
+        }
+
+        let aspect = window_width as f32 / window_height as f32;
+        if planet_selected && playback.is_none() {
+            // Volar hasta el cuerpo elegido, o alejarse hasta ver todo el sistema
+            let bodies = scene.bodies(current_planet, clock.time());
+            let target = if current_planet == 0 { system_extent(&bodies) } else { bodies[0] };
+            transition = Some(fly_to(&mut camera, target, aspect, transition_seconds, easing));
+        }
+
+        if let Some(elapsed) = playback.as_mut() {
+            // Durante la reproducción el recorrido controla la cámara y el reloj
+            *elapsed += delta_time.as_secs_f32();
+            if let Some((pose, time)) = camera_path.sample(*elapsed) {
+                pose.apply(&mut camera);
+                clock.jump_to(time as f64);
+            }
+            if *elapsed >= camera_path.duration() {
+                playback = None;
+            }
+            mouse.last_position = None;
+        } else if let Some(flight) = transition.as_mut() {
+            // La cámara no responde a los controles mientras vuela
+            if flight.update(delta_time.as_secs_f32(), &mut camera) {
+                transition = None;
+            }
+            mouse.last_position = None;
+        } else {
+            handle_input(&window, &mut camera, delta_time.as_secs_f32());
+            let picked = handle_mouse(&window, &mut camera, &mut mouse, (window_width, window_height), |(origin, direction)| {
+                scene.pick(current_planet, clock.time(), origin, direction)
+            });
+            camera.update(delta_time.as_secs_f32());
+            if let Some(body) = picked {
+                transition = Some(fly_to(&mut camera, body, aspect, transition_seconds, easing));
+            }
+        }
+        frame_times.update = frame_start.elapsed();
+
+        let render_start = Instant::now();
+        let mut profile = scene.render(&mut framebuffer, &camera, current_planet, clock.time(), &mut lod_state);
+        frame_times.render = render_start.elapsed();
+        let pipeline = profile.geometry();
+
+        if screenshot_requested {
+            // Con Shift también se guardan la profundidad y el buffer emisivo
+            let include_buffers = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+            match screenshot::capture(Path::new("screenshots"), &framebuffer, &camera, current_planet, clock.time(), include_buffers) {
+                Ok(paths) => for path in paths {
+                    println!("Saved {}", path.display());
+                },
+                Err(err) => eprintln!("Screenshot failed: {}", err),
+            }
+        }
 
-        handle_input(&window, &mut camera);
-        framebuffer.clear();
-        
-        uniforms.current_shader = current_planet;
-        uniforms.noise = create_noise(uniforms.current_shader);
-
-        uniforms.view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
-        uniforms.time = time as u32;
-
-        if current_planet == 2 {
-            // Renderizar Marte
-            uniforms.current_shader = 2;
-            uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-            render(&mut framebuffer, &uniforms, &vertex_arrays, time as u32);
-        
-            // Calcular y renderizar la luna de Marte
-            let moon_angle = time as f32 * moon_orbit_speed;
-            let moon_x = moon_distance * moon_angle.cos();
-            let moon_z = moon_distance * moon_angle.sin();
-        
-            let moon_translation = Vec3::new(moon_x, 0.0, moon_z);
-            let moon_model_matrix = create_model_matrix(moon_translation, moon_scale, Vec3::new(0.0, 0.0, 0.0));
-            uniforms.model_matrix = moon_model_matrix;
-        
-            let moon_shader_id = 8;
-            uniforms.current_shader = moon_shader_id;
-            render(&mut framebuffer, &uniforms, &moon_vertex_array, time as u32);
-        
-        } else if current_planet == 4 {
-            // Renderizar Saturno
-            uniforms.current_shader = 4;  // Shader para Saturno
-            uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-            render(&mut framebuffer, &uniforms, &vertex_arrays, time as u32);
-        
-            // Renderizar los anillos de Saturno
-            uniforms.current_shader = 9;  // Shader para los anillos
-            let ring_translation = translation;  // Posición de los anillos
-            let ring_scale = scale * 1.5;  // Tamaño de los anillos (más grande que el planeta)
-            uniforms.model_matrix = create_model_matrix(ring_translation, ring_scale, Vec3::new(0.0, 0.0, 0.0));
-            render(&mut framebuffer, &uniforms, &ring_vertex_array, time as u32);  // Reutiliza `ring_vertex_array` para la geometría de los anillos
-        
-        } else if current_planet == 7{
-             // Configurar para renderizar el Sol
-            uniforms.current_shader = 7;
-            uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-            render(&mut framebuffer, &uniforms, &vertex_arrays, time as u32);
-            
-            // Aplicar Gaussian Blur al buffer emisivo
-            // Asegúrate de que el kernel_size y sigma están correctamente configurados para tu necesidad
-            let kernel_size = 20; // Tamaño del kernel más grande para un desenfoque más suave y amplio
-            let sigma = 2.5; // Sigma para un desenfoque que produce un buen efecto de bloom
-            gaussian_blur(&mut framebuffer.emissive_buffer, framebuffer.width, framebuffer.height, kernel_size, sigma);
-            
-            // Aplicar Bloom
-            apply_bloom(&mut framebuffer.buffer, &framebuffer.emissive_buffer, framebuffer.width, framebuffer.height);
-            
+        // Por debajo de la resolución de la ventana se amplía con filtrado bilineal; por encima se promedia
+        let upscale_start = Instant::now();
+        if (display.width, display.height) != (window_width, window_height) {
+            display.resize(window_width, window_height);
+        }
+        let mut effects = scene.post_effects(current_planet);
+        if (framebuffer.width, framebuffer.height) == (window_width, window_height) {
+            display.buffer.copy_from_slice(&framebuffer.buffer);
+        } else if framebuffer.width < window_width {
+            display.buffer = resample_bilinear(&framebuffer.buffer, framebuffer.width, framebuffer.height, window_width, window_height);
+            effects.push("bilinear upscale");
         } else {
-            // Renderizar otros planetas sin lunas
-            uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-            render(&mut framebuffer, &uniforms, &vertex_arrays, time as u32);
+            display.buffer = resample(&framebuffer.buffer, framebuffer.width, framebuffer.height, window_width, window_height);
+            effects.push("supersampling");
+        }
+        frame_times.upscale = upscale_start.elapsed();
+        profile.add_stage("upscale", upscale_start, frame_times.upscale);
+
+        smoothed_frame_time += (delta_time.as_secs_f32() - smoothed_frame_time) * 0.1;
+        if show_hud {
+            draw_hud(&mut display, &HudInfo {
+                body: name,
+                fps: if smoothed_frame_time > 0.0 { 1.0 / smoothed_frame_time } else { 0.0 },
+                frame: frame_times,
+                pipeline,
+                profiling: capture.is_some(),
+                time: clock.time(),
+                clock_status: clock_status(&clock),
+                render_size: (framebuffer.width, framebuffer.height),
+                resolution_status: resolution_status(&resolution),
+                camera: &camera,
+                effects,
+            });
         }
-        
-        uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-        framebuffer.set_current_color(0xFFDDDD);
 
+        // El tiempo de trabajo del cuadro, sin contar la espera de la ventana, decide la próxima escala
+        resolution.update(frame_start.elapsed());
+
+        let present_start = Instant::now();
         window
-        .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
+        .update_with_buffer(&display.buffer, window_width, window_height)
         .unwrap();
+        frame_times.present = present_start.elapsed();
+        profile.add_stage("present", present_start, frame_times.present);
+        if let Some(profiler) = capture.as_mut() {
+            profiler.record(profile);
+        }
+    }
+}
+
+// Guarda la captura como CSV y como traza de Chrome en el directorio actual
+fn save_profile(profiler: &Profiler) {
+    println!("Profiled {} frames", profiler.frames().len());
+    let csv = Path::new("profile.csv");
+    match profiler.write_csv(csv) {
+        Ok(()) => println!("Saved {}", csv.display()),
+        Err(err) => eprintln!("Saving the profile failed: {}", err),
+    }
+    let trace = Path::new("profile.json");
+    match profiler.write_chrome_trace(trace) {
+        Ok(()) => println!("Saved {} (open it in chrome://tracing or ui.perfetto.dev)", trace.display()),
+        Err(err) => eprintln!("Saving the trace failed: {}", err),
+    }
+}
+
+// Sin los modelos en assets/models la escena se arma con geometría generada
+fn load_scene() -> Scene {
+    Scene::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load the models ({}), using generated meshes", err);
+        Scene::procedural()
+    })
+}
+
+// Modo de grabación: renderiza sin abrir la ventana
+fn run_recording(settings: &RecordSettings) {
+    let scene = load_scene();
+    match record(&scene, &Camera::default(), settings) {
+        Ok(frames) => println!("Wrote {} frames to {}", frames, settings.output.display()),
+        Err(err) => {
+            eprintln!("Recording failed: {}", err);
+            std::process::exit(1);
+        }
     }
 }
 
-fn handle_input(window: &Window, camera: &mut Camera) {
+// Opciones del visor:
+// --transition <segundos> --easing <linear|quad|cubic|expo|smoother> para los vuelos de la cámara
+// --render-scale <factor> para renderizar por debajo (< 1) o por encima (> 1) de la resolución de la ventana
+//   con una escala fija
+// --target-fps <fps> --min-scale <factor> --max-scale <factor> para el ajuste automático de resolución
+struct ViewerOptions {
+    transition_seconds: f32,
+    easing: Easing,
+    render_scale: Option<f32>,
+    target_fps: f32,
+    min_scale: f32,
+    max_scale: f32,
+}
+
+impl ViewerOptions {
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = ViewerOptions {
+            transition_seconds: 1.5,
+            easing: Easing::EaseInOutCubic,
+            render_scale: None,
+            target_fps: 30.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+        };
+        for pair in args.windows(2) {
+            let invalid = || format!("invalid value {:?} for {}", pair[1], pair[0]);
+            match pair[0].as_str() {
+                "--transition" => {
+                    options.transition_seconds = pair[1].parse().map_err(|_| invalid())?;
+                }
+                "--easing" => {
+                    options.easing = Easing::from_name(&pair[1]).ok_or_else(|| format!("unknown easing {:?}", pair[1]))?;
+                }
+                "--render-scale" => options.render_scale = Some(parse_scale(&pair[0], &pair[1])?),
+                "--min-scale" => options.min_scale = parse_scale(&pair[0], &pair[1])?,
+                "--max-scale" => options.max_scale = parse_scale(&pair[0], &pair[1])?,
+                "--target-fps" => {
+                    options.target_fps = pair[1].parse().map_err(|_| invalid())?;
+                    if options.target_fps <= 0.0 {
+                        return Err("--target-fps must be greater than zero".to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        if options.min_scale > options.max_scale {
+            return Err("--min-scale must not be greater than --max-scale".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_scale(option: &str, value: &str) -> Result<f32, String> {
+    let scale: f32 = value.parse().map_err(|_| format!("invalid value {:?} for {}", value, option))?;
+    if !(MIN_RENDER_SCALE..=MAX_RENDER_SCALE).contains(&scale) {
+        return Err(format!("{} must be between {} and {}", option, MIN_RENDER_SCALE, MAX_RENDER_SCALE));
+    }
+    Ok(scale)
+}
+
+const MIN_RENDER_SCALE: f32 = 0.25;
+const MAX_RENDER_SCALE: f32 = 2.0;
+
+fn render_size(window_width: usize, window_height: usize, scale: f32) -> (usize, usize) {
+    let width = (window_width as f32 * scale).round().max(1.0) as usize;
+    let height = (window_height as f32 * scale).round().max(1.0) as usize;
+    (width, height)
+}
+
+// Empieza un vuelo que encuadra el cuerpo dejando un margen alrededor
+fn fly_to(camera: &mut Camera, body: PlacedBody, aspect: f32, seconds: f32, easing: Easing) -> CameraTransition {
+    camera.set_mode(CameraMode::Orbit);
+    let from = CameraPose::of(camera);
+    let to = CameraPose::framing(camera, body.position, body.radius, aspect, 0.6);
+    CameraTransition::new(from, to, seconds, easing)
+}
+
+// Esfera centrada en el Sol que contiene todos los cuerpos
+fn system_extent(bodies: &[PlacedBody]) -> PlacedBody {
+    let radius = bodies.iter().map(|body| body.position.magnitude() + body.radius).fold(0.0, f32::max);
+    PlacedBody { shader: 0, position: Vec3::new(0.0, 0.0, 0.0), radius }
+}
+
+fn resolution_status(resolution: &DynamicResolution) -> String {
+    let scale = format!("{:.0}%", resolution.scale() * 100.0);
+    if resolution.is_enabled() {
+        format!("res {} auto ({} fps target)", scale, resolution.target_fps())
+    } else {
+        format!("res {}", scale)
+    }
+}
+
+fn clock_status(clock: &SimClock) -> String {
+    let mut status = format!("x{}", clock.speed());
+    if clock.is_reversed() {
+        status.push_str(" reversed");
+    }
+    if clock.is_paused() {
+        status.push_str(" paused");
+    }
+    if clock.fixed_timestep().is_some() {
+        status.push_str(" fixed step");
+    }
+    status
+}
+
+// `dt` son los segundos que duró el último cuadro
+fn handle_input(window: &Window, camera: &mut Camera, dt: f32) {
+    // Campo de visión: [ acerca y ] aleja
+    if window.is_key_down(Key::LeftBracket) {
+        camera.zoom_fov(0.98);
+    }
+    if window.is_key_down(Key::RightBracket) {
+        camera.zoom_fov(1.02);
+    }
+
+    // Con Ctrl las flechas desplazan el centro óptico (frustum asimétrico)
+    if window.is_key_down(Key::LeftCtrl) || window.is_key_down(Key::RightCtrl) {
+        let shift_speed = 0.01;
+        if window.is_key_down(Key::Left) {
+            camera.lens_shift.x -= shift_speed;
+        }
+        if window.is_key_down(Key::Right) {
+            camera.lens_shift.x += shift_speed;
+        }
+        if window.is_key_down(Key::Up) {
+            camera.lens_shift.y += shift_speed;
+        }
+        if window.is_key_down(Key::Down) {
+            camera.lens_shift.y -= shift_speed;
+        }
+        camera.set_orbit_velocity(Vec3::new(0.0, 0.0, 0.0));
+        return;
+    }
+
+    if camera.mode == CameraMode::FreeFly {
+        handle_fly_input(window, camera, dt);
+        return;
+    }
+
     let movement_speed = 1.0;
-    let rotation_speed = PI/50.0;
+    let rotation_speed = PI / 2.0; // radianes por segundo
     let zoom_speed = 0.1;
 
-    //  camera orbit controls
+    //  camera orbit controls: las teclas fijan la velocidad y la cámara acelera y frena con inercia
+    let mut orbit_velocity = Vec3::new(0.0, 0.0, 0.0);
     if window.is_key_down(Key::Left) {
-        camera.orbit(rotation_speed, 0.0);
+        orbit_velocity.x += rotation_speed;
     }
     if window.is_key_down(Key::Right) {
-        camera.orbit(-rotation_speed, 0.0);
+        orbit_velocity.x -= rotation_speed;
     }
     if window.is_key_down(Key::W) {
-        camera.orbit(0.0, -rotation_speed);
+        orbit_velocity.y -= rotation_speed;
     }
     if window.is_key_down(Key::S) {
-        camera.orbit(0.0, rotation_speed);
+        orbit_velocity.y += rotation_speed;
+    }
+    if window.is_key_down(Key::Z) {
+        orbit_velocity.z -= rotation_speed;
     }
+    if window.is_key_down(Key::X) {
+        orbit_velocity.z += rotation_speed;
+    }
+    camera.set_orbit_velocity(orbit_velocity);
 
     // Camera movement controls
     let mut movement = Vec3::new(0.0, 0.0, 0.0);
@@ -530,4 +587,133 @@ fn handle_input(window: &Window, camera: &mut Camera) {
     if window.is_key_down(Key::Down) {
         camera.zoom(-zoom_speed);
     }
-}
\ No newline at end of file
+}
+
+// Estado del ratón entre cuadros
+#[derive(Default)]
+struct MouseState {
+    last_position: Option<(f32, f32)>,
+    left_was_down: bool,
+    last_click: Option<Instant>,
+}
+
+// Arrastrar con el botón izquierdo orbita, con el derecho desplaza la vista, la rueda acerca y aleja,
+// y un doble clic devuelve el cuerpo bajo el cursor para volar hasta él. `pick` busca el cuerpo
+// que cruza el rayo (origen, dirección) dado
+fn handle_mouse(
+    window: &Window,
+    camera: &mut Camera,
+    mouse: &mut MouseState,
+    (width, height): (usize, usize),
+    pick: impl Fn((Vec3, Vec3)) -> Option<PlacedBody>,
+) -> Option<PlacedBody> {
+    let orbit_speed = 0.01; // radianes por píxel
+    let zoom_speed = 0.1;   // fracción de la distancia por paso de la rueda
+    let double_click = Duration::from_millis(400);
+
+    let Some((x, y)) = window.get_mouse_pos(MouseMode::Discard) else {
+        mouse.last_position = None;
+        return None;
+    };
+    let left = window.get_mouse_down(MouseButton::Left);
+    let right = window.get_mouse_down(MouseButton::Right);
+
+    if let Some((last_x, last_y)) = mouse.last_position {
+        let (dx, dy) = (x - last_x, y - last_y);
+        if dx != 0.0 || dy != 0.0 {
+            if left && camera.mode == CameraMode::Orbit {
+                camera.orbit(dx * orbit_speed, -dy * orbit_speed);
+            } else if left {
+                camera.look(dx * orbit_speed, -dy * orbit_speed, 0.0);
+            } else if right {
+                // El punto bajo el cursor en el plano del centro sigue al ratón
+                let before = focal_plane_point(camera, last_x, last_y, width, height);
+                let after = focal_plane_point(camera, x, y, width, height);
+                camera.translate(before - after);
+            }
+        }
+    }
+    mouse.last_position = Some((x, y));
+
+    if let Some((_, scroll)) = window.get_scroll_wheel() {
+        let distance = camera.distance();
+        let step = (distance * scroll * zoom_speed).min(distance * 0.5);
+        camera.zoom(step);
+    }
+
+    let mut picked = None;
+    if left && !mouse.left_was_down {
+        let now = Instant::now();
+        if mouse.last_click.is_some_and(|last| now - last < double_click) {
+            picked = pick(screen_ray(camera, x, y, width as f32, height as f32));
+            mouse.last_click = None;
+        } else {
+            mouse.last_click = Some(now);
+        }
+    }
+    mouse.left_was_down = left;
+    picked
+}
+
+fn focal_plane_point(camera: &Camera, x: f32, y: f32, width: usize, height: usize) -> Vec3 {
+    let forward = camera.forward();
+    let (origin, direction) = screen_ray(camera, x, y, width as f32, height as f32);
+    origin + direction * ((camera.center - origin).dot(&forward) / direction.dot(&forward))
+}
+
+// Las velocidades van por segundo y se escalan por `dt`, así el vuelo no depende de los cuadros por segundo
+fn handle_fly_input(window: &Window, camera: &mut Camera, dt: f32) {
+    let boost = if window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift) { 10.0 } else { 1.0 };
+    let movement_speed = 12.0 * boost * dt; // unidades por segundo
+    let rotation_speed = PI * 0.6 * dt;     // radianes por segundo
+
+    // Traslación relativa a la dirección de la vista: (derecha, arriba, adelante)
+    let mut movement = Vec3::new(0.0, 0.0, 0.0);
+    if window.is_key_down(Key::W) {
+        movement.z += movement_speed;
+    }
+    if window.is_key_down(Key::S) {
+        movement.z -= movement_speed;
+    }
+    if window.is_key_down(Key::D) {
+        movement.x += movement_speed;
+    }
+    if window.is_key_down(Key::A) {
+        movement.x -= movement_speed;
+    }
+    if window.is_key_down(Key::E) {
+        movement.y += movement_speed;
+    }
+    if window.is_key_down(Key::Q) {
+        movement.y -= movement_speed;
+    }
+    if movement.magnitude() > 0.0 {
+        camera.fly(movement);
+    }
+
+    // Orientación: flechas para yaw/pitch, Z y X para roll
+    let mut delta_yaw = 0.0;
+    let mut delta_pitch = 0.0;
+    let mut delta_roll = 0.0;
+    if window.is_key_down(Key::Left) {
+        delta_yaw -= rotation_speed;
+    }
+    if window.is_key_down(Key::Right) {
+        delta_yaw += rotation_speed;
+    }
+    if window.is_key_down(Key::Up) {
+        delta_pitch += rotation_speed;
+    }
+    if window.is_key_down(Key::Down) {
+        delta_pitch -= rotation_speed;
+    }
+    if window.is_key_down(Key::Z) {
+        delta_roll -= rotation_speed;
+    }
+    if window.is_key_down(Key::X) {
+        delta_roll += rotation_speed;
+    }
+    if delta_yaw != 0.0 || delta_pitch != 0.0 || delta_roll != 0.0 {
+        camera.look(delta_yaw, delta_pitch, delta_roll);
+    }
+}
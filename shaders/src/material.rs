@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use nalgebra_glm::Vec3;
+use crate::obj::{parse_error, parse_floats, ObjError};
+
+// Un material de un archivo MTL. Los colores van de 0 a 1 y las rutas de las texturas ya
+// incluyen el directorio del MTL, así que se pueden abrir tal cual
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub name: String,
+    pub ambient: Vec3,
+    pub diffuse: Vec3,
+    pub specular: Vec3,
+    pub emissive: Vec3,
+    pub shininess: f32,
+    pub opacity: f32,
+    pub diffuse_map: Option<PathBuf>,
+    pub specular_map: Option<PathBuf>,
+    pub emissive_map: Option<PathBuf>,
+    pub bump_map: Option<PathBuf>,
+}
+
+impl Material {
+    // Valores por defecto de la especificación MTL: difuso gris claro, sin brillo ni emisión
+    pub fn new(name: &str) -> Self {
+        Material {
+            name: name.to_string(),
+            ambient: Vec3::new(0.0, 0.0, 0.0),
+            diffuse: Vec3::new(0.8, 0.8, 0.8),
+            specular: Vec3::new(0.0, 0.0, 0.0),
+            emissive: Vec3::new(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            opacity: 1.0,
+            diffuse_map: None,
+            specular_map: None,
+            emissive_map: None,
+            bump_map: None,
+        }
+    }
+
+    pub fn is_emissive(&self) -> bool {
+        self.emissive != Vec3::new(0.0, 0.0, 0.0) || self.emissive_map.is_some()
+    }
+}
+
+pub fn load_materials(filename: impl AsRef<Path>) -> Result<Vec<Material>, ObjError> {
+    let path = filename.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|source| ObjError::Io { path: path.to_path_buf(), source })?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut materials: Vec<Material> = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+        let args: Vec<&str> = words.collect();
+
+        if keyword == "newmtl" {
+            let name = args.join(" ");
+            if name.is_empty() {
+                return Err(parse_error(path, number, "newmtl without a material name"));
+            }
+            materials.push(Material::new(&name));
+            continue;
+        }
+
+        let Some(material) = materials.last_mut() else {
+            return Err(parse_error(path, number, &format!("{} before any newmtl", keyword)));
+        };
+        match keyword {
+            "Ka" => material.ambient = parse_color(&args, path, number)?,
+            "Kd" => material.diffuse = parse_color(&args, path, number)?,
+            "Ks" => material.specular = parse_color(&args, path, number)?,
+            "Ke" => material.emissive = parse_color(&args, path, number)?,
+            "Ns" => material.shininess = parse_floats(&args, 1, path, number)?[0],
+            "d" => material.opacity = parse_floats(&args, 1, path, number)?[0],
+            "Tr" => material.opacity = 1.0 - parse_floats(&args, 1, path, number)?[0],
+            "map_Kd" => material.diffuse_map = Some(parse_map(&args, directory, path, number)?),
+            "map_Ks" => material.specular_map = Some(parse_map(&args, directory, path, number)?),
+            "map_Ke" => material.emissive_map = Some(parse_map(&args, directory, path, number)?),
+            "map_Bump" | "map_bump" | "bump" | "norm" => {
+                material.bump_map = Some(parse_map(&args, directory, path, number)?);
+            }
+            // Modelo de iluminación, índice de refracción y demás no los usan nuestros shaders
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+// Un solo número es un gris
+fn parse_color(args: &[&str], path: &Path, line: usize) -> Result<Vec3, ObjError> {
+    let values = parse_floats(args, 1, path, line)?;
+    match values.as_slice() {
+        [gray] => Ok(Vec3::new(*gray, *gray, *gray)),
+        [r, g, b, ..] => Ok(Vec3::new(*r, *g, *b)),
+        _ => Err(parse_error(path, line, &format!("expected 1 or 3 numbers, found {}", values.len()))),
+    }
+}
+
+// Las opciones como "-bm 0.5" van antes de la ruta, que es siempre la última palabra
+fn parse_map(args: &[&str], directory: &Path, path: &Path, line: usize) -> Result<PathBuf, ObjError> {
+    match args.last() {
+        Some(file) => Ok(directory.join(file)),
+        None => Err(parse_error(path, line, "texture map without a file")),
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use nalgebra_glm::{Vec2, Vec3};
+use crate::obj::{Mesh, Obj};
+use crate::vertex::{tangent_basis, Vertex};
+
+// Ángulo entre caras a partir del cual la arista se considera un pliegue y no se suaviza
+pub const DEFAULT_CREASE_ANGLE: f32 = std::f32::consts::PI / 3.0;
+
+// Posición, coordenada de textura y normal como bits, para comparar vértices exactamente
+type VertexKey = ([u32; 3], [u32; 2], [u32; 3]);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalMode {
+    // Promedio de las caras que comparten la posición, pesado por el ángulo de cada esquina.
+    // Las caras que difieren más que `crease_angle` (en radianes) no se mezclan
+    Smooth { crease_angle: f32 },
+    // Cada cara con su propia normal
+    Flat,
+}
+
+// Geometría con un buffer de vértices únicos y tres índices por triángulo, para `draw_indexed`
+#[derive(Debug, Clone, Default)]
+pub struct IndexedMesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
+
+impl IndexedMesh {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    // Radio de la esfera centrada en el origen que envuelve todos los vértices
+    pub fn bounding_radius(&self) -> f32 {
+        self.vertices.iter().map(|vertex| vertex.position.magnitude()).fold(0.0, f32::max)
+    }
+
+    // Expande los índices en la lista plana que usa `render`
+    pub fn get_vertex_array(&self) -> Vec<Vertex> {
+        self.indices.iter().map(|&index| self.vertices[index as usize].clone()).collect()
+    }
+
+    // Agrega otro mesh al final, desplazando sus índices
+    pub fn append(&mut self, other: &IndexedMesh) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(other.vertices.iter().cloned());
+        self.indices.extend(other.indices.iter().map(|index| index + offset));
+    }
+}
+
+// Fracción del umbral que hay que pasar de más para cambiar de nivel, para que un objeto justo en
+// el límite no alterne entre dos niveles en cada cuadro
+pub const LOD_HYSTERESIS: f32 = 0.15;
+
+// Versiones de un mismo objeto de la más detallada a la más simple. Cada nivel después del primero
+// se usa cuando el objeto se ve con un radio menor que su umbral, en píxeles
+#[derive(Debug, Clone)]
+pub struct LodMesh {
+    levels: Vec<(IndexedMesh, f32)>,
+    radius: f32,
+}
+
+impl LodMesh {
+    pub fn new(finest: IndexedMesh) -> Self {
+        LodMesh { radius: finest.bounding_radius(), levels: vec![(finest, f32::INFINITY)] }
+    }
+
+    // Agrega un nivel más simple que el último, usado por debajo de `below_radius` píxeles
+    pub fn with_level(mut self, mesh: IndexedMesh, below_radius: f32) -> Self {
+        let last = self.levels.last().unwrap().1;
+        self.levels.push((mesh, below_radius.min(last)));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub fn level(&self, index: usize) -> &IndexedMesh {
+        &self.levels[index].0
+    }
+
+    pub fn finest(&self) -> &IndexedMesh {
+        self.level(0)
+    }
+
+    // Radio envolvente del nivel más detallado; los demás aproximan la misma forma
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    // Nivel para un objeto que se ve con `radius` píxeles de radio. Con el nivel del cuadro anterior
+    // sólo se cambia si el radio se aleja del umbral más que la histéresis
+    pub fn select(&self, radius: f32, previous: Option<usize>) -> usize {
+        let below = |factor: f32| self.levels[1..].iter().filter(|(_, threshold)| radius < threshold * factor).count();
+        match previous {
+            Some(previous) => previous.clamp(below(1.0 - LOD_HYSTERESIS), below(1.0 + LOD_HYSTERESIS)),
+            None => below(1.0),
+        }
+    }
+}
+
+impl Obj {
+    // Todos los meshes juntos como geometría indexada
+    pub fn get_indexed_mesh(&self) -> IndexedMesh {
+        let mut indexed = IndexedMesh::default();
+        for mesh in self.meshes() {
+            indexed.append(&mesh.get_indexed_mesh());
+        }
+        indexed
+    }
+}
+
+impl Mesh {
+    pub fn get_indexed_mesh(&self) -> IndexedMesh {
+        let vertices = (0..self.vertices.len()).map(|index| {
+            let mut vertex = Vertex::new(self.vertices[index], self.normals[index], self.texcoords[index]);
+            vertex.tangent = self.tangents[index];
+            vertex.bitangent = self.bitangents[index];
+            vertex
+        }).collect();
+        IndexedMesh { vertices, indices: self.indices.clone() }
+    }
+
+    // Reemplaza las normales. Los vértices se separan donde una misma esquina necesita normales
+    // distintas y se juntan donde ya no hace falta, así que los índices cambian. Las tangentes
+    // se recalculan para seguir a las normales
+    pub fn compute_normals(&mut self, mode: NormalMode) {
+        let triangles: Vec<[usize; 3]> = self.indices.chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .collect();
+        let face_normals: Vec<Vec3> = triangles.iter().map(|t| face_normal(&self.vertices, t)).collect();
+        let corner_angles: Vec<[f32; 3]> = triangles.iter().map(|t| corner_angles(&self.vertices, t)).collect();
+
+        // Las esquinas se agrupan por posición y no por índice: las costuras de textura del
+        // archivo duplican vértices que deben suavizarse juntos
+        let mut shared: HashMap<[u32; 3], Vec<(usize, usize)>> = HashMap::new();
+        for (face, triangle) in triangles.iter().enumerate() {
+            for (corner, &index) in triangle.iter().enumerate() {
+                shared.entry(position_key(self.vertices[index])).or_default().push((face, corner));
+            }
+        }
+
+        let min_cos = match mode {
+            NormalMode::Smooth { crease_angle } => crease_angle.cos(),
+            NormalMode::Flat => 1.0,
+        };
+
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut texcoords = Vec::new();
+        let mut indices = Vec::with_capacity(self.indices.len());
+        // Vértices iguales en posición, textura y normal se comparten, aunque antes estuvieran separados
+        let mut remap: HashMap<VertexKey, u32> = HashMap::new();
+
+        for (face, triangle) in triangles.iter().enumerate() {
+            let own = face_normals[face];
+            for &index in triangle {
+                let normal = match mode {
+                    NormalMode::Flat => own,
+                    NormalMode::Smooth { .. } => {
+                        let sum = shared[&position_key(self.vertices[index])].iter()
+                            .filter(|&&(other, _)| face_normals[other].dot(&own) >= min_cos)
+                            .fold(Vec3::zeros(), |sum, &(other, corner)| {
+                                sum + face_normals[other] * corner_angles[other][corner]
+                            });
+                        if sum.magnitude_squared() > 0.0 { sum.normalize() } else { own }
+                    }
+                };
+                let normal = if normal.magnitude_squared() > 0.0 { normal } else { Vec3::new(0.0, 1.0, 0.0) };
+
+                let texcoord = self.texcoords[index];
+                let texcoord_key = [texcoord.x.to_bits(), texcoord.y.to_bits()];
+                let key = (position_key(self.vertices[index]), texcoord_key, position_key(normal));
+                let new_index = *remap.entry(key).or_insert_with(|| {
+                    vertices.push(self.vertices[index]);
+                    normals.push(normal);
+                    texcoords.push(texcoord);
+                    (vertices.len() - 1) as u32
+                });
+                indices.push(new_index);
+            }
+        }
+
+        self.vertices = vertices;
+        self.normals = normals;
+        self.texcoords = texcoords;
+        self.indices = indices;
+        self.compute_tangents();
+    }
+
+    // Tangentes a partir de las coordenadas de textura, al estilo de MikkTSpace: se acumulan por
+    // vértice pesadas por el ángulo de cada esquina, se hacen ortogonales a la normal y la
+    // bitangente conserva el sentido de v (negativo en las partes con la textura espejada)
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![Vec3::zeros(); self.vertices.len()];
+        let mut bitangents = vec![Vec3::zeros(); self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let t = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            let Some((tangent, bitangent)) = uv_derivatives(&self.vertices, &self.texcoords, &t) else {
+                continue;
+            };
+            for (corner, angle) in corner_angles(&self.vertices, &t).into_iter().enumerate() {
+                tangents[t[corner]] += tangent * angle;
+                bitangents[t[corner]] += bitangent * angle;
+            }
+        }
+
+        for (index, normal) in self.normals.iter().enumerate() {
+            let normal = normal.normalize();
+            let tangent = tangents[index] - normal * normal.dot(&tangents[index]);
+            if tangent.magnitude_squared() < 1e-12 {
+                // Sin coordenadas de textura útiles cualquier base sirve
+                (tangents[index], bitangents[index]) = tangent_basis(&normal);
+                continue;
+            }
+            let tangent = tangent.normalize();
+            let handedness = if normal.cross(&tangent).dot(&bitangents[index]) < 0.0 { -1.0 } else { 1.0 };
+            tangents[index] = tangent;
+            bitangents[index] = normal.cross(&tangent) * handedness;
+        }
+
+        self.tangents = tangents;
+        self.bitangents = bitangents;
+    }
+}
+
+// Normal unitaria de la cara, o cero si el triángulo está degenerado
+fn face_normal(vertices: &[Vec3], t: &[usize; 3]) -> Vec3 {
+    let normal = (vertices[t[1]] - vertices[t[0]]).cross(&(vertices[t[2]] - vertices[t[0]]));
+    if normal.magnitude_squared() > 0.0 { normal.normalize() } else { normal }
+}
+
+// Ángulo interior del triángulo en cada esquina
+fn corner_angles(vertices: &[Vec3], t: &[usize; 3]) -> [f32; 3] {
+    let angle = |corner: usize| {
+        let origin = vertices[t[corner]];
+        let a = vertices[t[(corner + 1) % 3]] - origin;
+        let b = vertices[t[(corner + 2) % 3]] - origin;
+        if a.magnitude_squared() == 0.0 || b.magnitude_squared() == 0.0 {
+            return 0.0;
+        }
+        a.normalize().dot(&b.normalize()).clamp(-1.0, 1.0).acos()
+    };
+    [angle(0), angle(1), angle(2)]
+}
+
+// Derivadas de la posición respecto a u y a v sobre el triángulo
+fn uv_derivatives(vertices: &[Vec3], texcoords: &[Vec2], t: &[usize; 3]) -> Option<(Vec3, Vec3)> {
+    let edge1 = vertices[t[1]] - vertices[t[0]];
+    let edge2 = vertices[t[2]] - vertices[t[0]];
+    let duv1 = texcoords[t[1]] - texcoords[t[0]];
+    let duv2 = texcoords[t[2]] - texcoords[t[0]];
+    let determinant = duv1.x * duv2.y - duv2.x * duv1.y;
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+    let tangent = (edge1 * duv2.y - edge2 * duv1.y) / determinant;
+    let bitangent = (edge2 * duv1.x - edge1 * duv2.x) / determinant;
+    Some((tangent, bitangent))
+}
+
+pub(crate) fn position_key(v: Vec3) -> [u32; 3] {
+    // Se suma 0.0 para que -0.0 y 0.0 den la misma clave
+    [(v.x + 0.0).to_bits(), (v.y + 0.0).to_bits(), (v.z + 0.0).to_bits()]
+}
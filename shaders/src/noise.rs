@@ -0,0 +1,125 @@
+use fastnoise_lite::{FastNoiseLite, NoiseType, FractalType};
+use nalgebra_glm::Vec3;
+
+// Ruido sobre la superficie de un cuerpo. Se evalúa en 3D en la dirección del punto desde el centro
+// del modelo, así que no hay costuras ni espejos y los detalles miden lo mismo en los polos que en
+// el ecuador. Con `scale` 1 la esfera unitaria cubre el mismo rango que (x, y) en `get_noise_2d`;
+// escalas mayores dan detalles más finos. El resultado no depende del radio del mesh
+pub fn sample_sphere(noise: &FastNoiseLite, point: &Vec3, scale: f32) -> f32 {
+    sample_sphere_at(noise, point, scale, Vec3::new(0.0, 0.0, 0.0))
+}
+
+// Igual que `sample_sphere` pero moviendo el dominio del ruido en `offset`, ya en unidades
+// escaladas, para animar capas como nubes o atmósferas
+pub fn sample_sphere_at(noise: &FastNoiseLite, point: &Vec3, scale: f32, offset: Vec3) -> f32 {
+    let radius = point.magnitude();
+    let direction = if radius > 0.0 { point / radius } else { *point };
+    let p = direction * scale + offset;
+    noise.get_noise_3d(p.x, p.y, p.z)
+}
+
+// Si el shader lee el ruido de `create_noise` al colorear o al dar relieve. El Sol, Saturno y los
+// anillos no lo usan
+pub fn samples_noise(shader: u8) -> bool {
+    matches!(shader, 1 | 2 | 3 | 5 | 6 | 8)
+}
+
+pub fn create_noise(current_shader: u8) -> FastNoiseLite {
+    match current_shader {
+        1 => create_earth_noise(),
+        2 => create_mars_noise(),
+        3 => create_mercury_noise(),
+        4 => FastNoiseLite::new(),
+        5 => create_jupiter_noise(),
+        6 => create_urano_noise(), 
+        8 => create_moon_noise(),
+        9 => FastNoiseLite::new(),
+        _ => create_earth_noise(),  
+    }
+}
+
+fn create_earth_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(1337);
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2S));
+    noise.set_fractal_type(Some(FractalType::Ridged));
+    noise.set_fractal_octaves(Some(5)); // Octavas para mayor detalle
+    noise.set_fractal_lacunarity(Some(3.0)); // Lacunaridad para escalado de frecuencia
+    noise.set_fractal_gain(Some(0.5)); // Ganancia para el escalado de amplitud
+    noise.set_frequency(Some(0.5)); 
+
+    noise
+}
+
+pub fn create_cloud_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(40);  
+    noise.set_noise_type(Some(NoiseType::Perlin)); 
+    noise.set_fractal_type(Some(FractalType::FBm));
+    noise.set_fractal_octaves(Some(2));  // Menos octavas para menos detalles
+    noise.set_fractal_lacunarity(Some(3.0));
+    noise.set_fractal_gain(Some(0.5));
+    noise.set_frequency(Some(0.01));  // Baja frecuencia para nubes grandes y suaves
+    noise
+}
+
+fn create_mars_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(1234);
+    noise.set_noise_type(Some(NoiseType::Perlin));
+    noise.set_fractal_type(Some(FractalType::Ridged));
+    noise.set_fractal_octaves(Some(4));
+    noise.set_fractal_lacunarity(Some(2.0));
+    noise.set_fractal_gain(Some(0.5));
+    noise.set_frequency(Some(1.5)); 
+    noise
+}
+
+fn create_moon_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(4321);
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise.set_fractal_type(Some(FractalType::PingPong));
+    noise.set_fractal_octaves(Some(2));
+    noise.set_fractal_lacunarity(Some(2.0));
+    noise.set_fractal_gain(Some(0.5));
+    noise.set_frequency(Some(3.0));  
+    noise
+}
+
+fn create_mercury_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(4321);
+    noise.set_noise_type(Some(NoiseType::Perlin));
+    noise.set_fractal_type(Some(FractalType::PingPong));
+    noise.set_fractal_octaves(Some(5));
+    noise.set_fractal_lacunarity(Some(2.0));
+    noise.set_fractal_gain(Some(1.0));
+    noise.set_frequency(Some(5.0));  
+    noise
+}
+
+fn create_jupiter_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(5678); // Puedes elegir cualquier semilla
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2)); // OpenSimplex2 produce un ruido más suave
+    noise.set_fractal_type(Some(FractalType::DomainWarpProgressive)); // Añade complejidad fractal
+    noise.set_fractal_octaves(Some(6)); // Más octavas para más detalle
+    noise.set_fractal_lacunarity(Some(2.0)); // Lacunaridad estándar
+    noise.set_fractal_gain(Some(0.5)); // Ganancia menor para detalles finos
+    noise.set_frequency(Some(2.0)); // Ajusta la escala del ruido
+    noise
+}
+
+pub fn create_jupiter_band_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(7890); // Nueva semilla
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise.set_frequency(Some(1.0));
+    noise.set_fractal_type(Some(FractalType::FBm));
+    noise
+}
+
+fn create_urano_noise() -> FastNoiseLite {
+    let mut noise = FastNoiseLite::with_seed(2021);
+    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+    noise.set_fractal_type(Some(FractalType::Ridged));
+    noise.set_fractal_octaves(Some(4));
+    noise.set_fractal_lacunarity(Some(2.0));
+    noise.set_fractal_gain(Some(0.4));
+    noise.set_frequency(Some(0.2));
+    noise
+}
@@ -1,62 +1,387 @@
-use tobj;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use nalgebra_glm::{Vec2, Vec3};
+use crate::material::{load_materials, Material};
+use crate::mesh::{NormalMode, DEFAULT_CREASE_ANGLE};
 use crate::vertex::Vertex;
 
+// Error al leer un OBJ o un MTL, con el archivo y, si es de formato, la línea
+#[derive(Debug)]
+pub enum ObjError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, line: usize, message: String },
+}
+
+impl ObjError {
+    pub fn path(&self) -> &Path {
+        match self {
+            ObjError::Io { path, .. } | ObjError::Parse { path, .. } => path,
+        }
+    }
+
+    // Línea (desde 1) donde está el problema, si es un error de formato
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ObjError::Io { .. } => None,
+            ObjError::Parse { line, .. } => Some(*line),
+        }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ObjError::Parse { path, line, message } => write!(f, "{}:{}: {}", path.display(), line, message),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
 pub struct Obj {
-    meshes: Vec<Mesh>,
+    pub(crate) meshes: Vec<Mesh>,
+    pub(crate) materials: Vec<Material>,
+    // Archivos MTL que nombra el OBJ, con la ruta desde donde se leyó, para volver a nombrarlos en `save`
+    pub(crate) libraries: Vec<PathBuf>,
+}
+
+// Una parte del modelo que usa un solo material. Los índices apuntan a los vértices del mismo mesh
+pub struct Mesh {
+    pub name: String,
+    pub material: Option<String>,
+    pub(crate) vertices: Vec<Vec3>,
+    pub(crate) normals: Vec<Vec3>,
+    pub(crate) tangents: Vec<Vec3>,
+    pub(crate) bitangents: Vec<Vec3>,
+    pub(crate) texcoords: Vec<Vec2>,
+    pub(crate) indices: Vec<u32>,
+    // Si alguna esquina del archivo no traía normal
+    missing_normals: bool,
 }
 
-struct Mesh {
-    vertices: Vec<Vec3>,
+impl Mesh {
+    fn new(name: &str, material: Option<String>) -> Self {
+        Mesh {
+            name: name.to_string(),
+            material,
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            tangents: Vec::new(),
+            bitangents: Vec::new(),
+            texcoords: Vec::new(),
+            indices: Vec::new(),
+            missing_normals: false,
+        }
+    }
+
+    // Mesh armado en código, con normales y coordenadas de textura por vértice. Calcula las tangentes
+    pub(crate) fn from_buffers(name: &str, vertices: Vec<Vec3>, normals: Vec<Vec3>, texcoords: Vec<Vec2>, indices: Vec<u32>) -> Self {
+        let mut mesh = Mesh { vertices, normals, texcoords, indices, ..Mesh::new(name, None) };
+        mesh.compute_tangents();
+        mesh
+    }
+
+    // Vértices distintos, antes de repetirlos por triángulo
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    // Tres vértices por triángulo, listos para `render`
+    pub fn get_vertex_array(&self) -> Vec<Vertex> {
+        self.get_indexed_mesh().get_vertex_array()
+    }
+}
+
+// Índices de posición, coordenada de textura y normal de una esquina de cara, ya desde 0
+type Corner = (usize, Option<usize>, Option<usize>);
+
+// Lo que se va leyendo del OBJ antes de separarlo por meshes
+struct ObjParser<'a> {
+    path: &'a Path,
+    positions: Vec<Vec3>,
     normals: Vec<Vec3>,
     texcoords: Vec<Vec2>,
-    indices: Vec<u32>,
+    meshes: Vec<Mesh>,
+    // Esquinas ya agregadas al último mesh, para compartir vértices iguales
+    corners: HashMap<Corner, u32>,
+    group: String,
 }
 
 impl Obj {
-    pub fn load(filename: &str) -> Result<Self, tobj::LoadError> {
-        let (models, _) = tobj::load_obj(filename, &tobj::LoadOptions {
-            single_index: true,
-            triangulate: true,
-            ..Default::default()
-        })?;
-
-        let meshes = models.into_iter().map(|model| {
-            let mesh = model.mesh;
-            Mesh {
-                vertices: mesh.positions.chunks(3)
-                    .map(|v| Vec3::new(v[0], v[1], v[2]))
-                    .collect(),
-                normals: mesh.normals.chunks(3)
-                    .map(|n| Vec3::new(n[0], n[1], n[2]))
-                    .collect(),
-                texcoords: mesh.texcoords.chunks(2)
-                    .map(|t| Vec2::new(t[0], 1.0 - t[1]))
-                    .collect(),
-                indices: mesh.indices,
-            }
-        }).collect();
-
-        Ok(Obj { meshes })
+    pub fn load(filename: impl AsRef<Path>) -> Result<Self, ObjError> {
+        let path = filename.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|source| ObjError::Io { path: path.to_path_buf(), source })?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let mut parser = ObjParser {
+            path,
+            positions: Vec::new(),
+            normals: Vec::new(),
+            texcoords: Vec::new(),
+            meshes: Vec::new(),
+            corners: HashMap::new(),
+            group: String::new(),
+        };
+        let mut materials = Vec::new();
+        let mut libraries = Vec::new();
+
+        for (number, line) in source.lines().enumerate() {
+            let number = number + 1;
+            let line = line.split('#').next().unwrap_or("");
+            let mut words = line.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+
+            match keyword {
+                "v" => {
+                    let v = parse_floats(&args, 3, path, number)?;
+                    parser.positions.push(Vec3::new(v[0], v[1], v[2]));
+                }
+                "vn" => {
+                    let n = parse_floats(&args, 3, path, number)?;
+                    parser.normals.push(Vec3::new(n[0], n[1], n[2]));
+                }
+                "vt" => {
+                    // La v es opcional; se invierte porque las imágenes empiezan arriba
+                    let t = parse_floats(&args, 1, path, number)?;
+                    parser.texcoords.push(Vec2::new(t[0], 1.0 - t.get(1).copied().unwrap_or(0.0)));
+                }
+                "f" => parser.face(&args, number)?,
+                "o" | "g" => {
+                    parser.group = args.join(" ");
+                    parser.start_mesh(None);
+                }
+                "usemtl" => {
+                    let name = args.join(" ");
+                    if name.is_empty() {
+                        return Err(parse_error(path, number, "usemtl without a material name"));
+                    }
+                    parser.start_mesh(Some(name));
+                }
+                "mtllib" => {
+                    for library in &args {
+                        let library = directory.join(library);
+                        // Un MTL que no existe no impide usar la geometría: los meshes se quedan
+                        // con el nombre del material pero sin sus propiedades
+                        match load_materials(&library) {
+                            Ok(loaded) => materials.extend(loaded),
+                            Err(ObjError::Io { path: missing, source }) if source.kind() == io::ErrorKind::NotFound => {
+                                eprintln!("warning: {}:{}: {} not found, its materials are ignored", path.display(), number, missing.display());
+                            }
+                            Err(err) => return Err(err),
+                        }
+                        libraries.push(library);
+                    }
+                }
+                // Suavizado, líneas, puntos y demás no afectan a los triángulos
+                _ => {}
+            }
+        }
+
+        // Sin normales en el archivo la iluminación saldría plana, así que se calculan
+        let mut meshes: Vec<Mesh> = parser.meshes.into_iter().filter(|mesh| !mesh.indices.is_empty()).collect();
+        for mesh in &mut meshes {
+            if mesh.missing_normals {
+                mesh.compute_normals(NormalMode::Smooth { crease_angle: DEFAULT_CREASE_ANGLE });
+            } else {
+                mesh.compute_tangents();
+            }
+        }
+        Ok(Obj { meshes, materials, libraries })
     }
 
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    // Material de un mesh, si su MTL lo define
+    pub fn material(&self, mesh: &Mesh) -> Option<&Material> {
+        let name = mesh.material.as_ref()?;
+        self.materials.iter().find(|material| &material.name == name)
+    }
+
+    // Reemplaza las normales de todos los meshes, también las que venían en el archivo
+    pub fn compute_normals(&mut self, mode: NormalMode) {
+        for mesh in &mut self.meshes {
+            mesh.compute_normals(mode);
+        }
+    }
+
+    // Todos los meshes juntos, para dibujar el modelo con un solo shader
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
-        let mut vertices = Vec::new();
+        self.meshes.iter().flat_map(|mesh| mesh.get_vertex_array()).collect()
+    }
+
+    // Escribe el modelo como OBJ, un objeto por mesh con su material. Los MTL no se copian: el
+    // archivo los nombra por su ruta desde el directorio de salida, así siguen encontrando sus texturas
+    pub fn save(&self, filename: impl AsRef<Path>) -> Result<(), ObjError> {
+        let path = filename.as_ref();
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut out = String::new();
+        for library in &self.libraries {
+            out.push_str(&format!("mtllib {}\n", relative_path(library, directory)));
+        }
 
+        // Los índices del OBJ son globales y empiezan en 1
+        let mut offset = 1;
         for mesh in &self.meshes {
-            for &index in &mesh.indices {
-                let position = mesh.vertices[index as usize];
-                let normal = mesh.normals.get(index as usize)
-                    .cloned()
-                    .unwrap_or(Vec3::new(0.0, 1.0, 0.0));
-                let tex_coords = mesh.texcoords.get(index as usize)
-                    .cloned()
-                    .unwrap_or(Vec2::new(0.0, 0.0));
+            out.push_str(&format!("o {}\n", mesh.name));
+            if let Some(material) = &mesh.material {
+                out.push_str(&format!("usemtl {}\n", material));
+            }
+            for v in &mesh.vertices {
+                out.push_str(&format!("v {} {} {}\n", v.x, v.y, v.z));
+            }
+            for t in &mesh.texcoords {
+                out.push_str(&format!("vt {} {}\n", t.x, 1.0 - t.y));
+            }
+            for n in &mesh.normals {
+                out.push_str(&format!("vn {} {} {}\n", n.x, n.y, n.z));
+            }
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|k| triangle[k] as usize + offset);
+                out.push_str(&format!("f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}\n"));
+            }
+            offset += mesh.vertices.len();
+        }
 
-                vertices.push(Vertex::new(position, normal, tex_coords));
+        fs::write(path, out).map_err(|source| ObjError::Io { path: path.to_path_buf(), source })
+    }
+}
+
+impl ObjParser<'_> {
+    // Empieza un mesh nuevo con el grupo actual. Sin material conserva el del mesh anterior
+    fn start_mesh(&mut self, material: Option<String>) {
+        let material = material.or_else(|| self.meshes.last().and_then(|mesh| mesh.material.clone()));
+        if let Some(last) = self.meshes.last_mut() {
+            if last.indices.is_empty() {
+                last.name = self.group.clone();
+                last.material = material;
+                return;
             }
         }
+        self.meshes.push(Mesh::new(&self.group, material));
+        self.corners.clear();
+    }
 
-        vertices
+    fn face(&mut self, args: &[&str], number: usize) -> Result<(), ObjError> {
+        if args.len() < 3 {
+            return Err(parse_error(self.path, number, "a face needs at least 3 vertices"));
+        }
+        let corners = args.iter()
+            .map(|corner| self.corner(corner, number))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.meshes.is_empty() {
+            self.meshes.push(Mesh::new(&self.group, None));
+        }
+        let indices: Vec<u32> = corners.into_iter().map(|corner| self.vertex_index(corner)).collect();
+
+        // Los polígonos se dividen en abanico desde el primer vértice
+        let mesh = self.meshes.last_mut().unwrap();
+        for i in 1..indices.len() - 1 {
+            mesh.indices.extend([indices[0], indices[i], indices[i + 1]]);
+        }
+        Ok(())
+    }
+
+    // Interpreta "v", "v/vt", "v//vn" o "v/vt/vn"; los índices negativos cuentan desde el final
+    fn corner(&self, corner: &str, number: usize) -> Result<Corner, ObjError> {
+        let mut parts = corner.split('/');
+        let resolve = |part: Option<&str>, count: usize, what: &str| -> Result<Option<usize>, ObjError> {
+            let part = match part {
+                None | Some("") => return Ok(None),
+                Some(part) => part,
+            };
+            let index: i64 = part.parse()
+                .map_err(|_| parse_error(self.path, number, &format!("invalid {} index {:?}", what, part)))?;
+            let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+            if index == 0 || resolved < 0 || resolved >= count as i64 {
+                return Err(parse_error(
+                    self.path, number,
+                    &format!("{} index {} is out of range, there are {}", what, index, count),
+                ));
+            }
+            Ok(Some(resolved as usize))
+        };
+
+        let position = resolve(parts.next(), self.positions.len(), "vertex")?
+            .ok_or_else(|| parse_error(self.path, number, &format!("face corner {:?} has no vertex", corner)))?;
+        let texcoord = resolve(parts.next(), self.texcoords.len(), "texture coordinate")?;
+        let normal = resolve(parts.next(), self.normals.len(), "normal")?;
+        Ok((position, texcoord, normal))
+    }
+
+    // Índice del vértice dentro del mesh actual, agregándolo si esa combinación no existía
+    fn vertex_index(&mut self, corner: Corner) -> u32 {
+        let mesh = self.meshes.last_mut().unwrap();
+        *self.corners.entry(corner).or_insert_with(|| {
+            let (position, texcoord, normal) = corner;
+            // Las esquinas sin coordenada de textura usan (0, 0); si falta la normal se calcula al terminar
+            mesh.vertices.push(self.positions[position]);
+            mesh.texcoords.push(texcoord.map_or(Vec2::new(0.0, 0.0), |texcoord| self.texcoords[texcoord]));
+            mesh.normals.push(normal.map_or(Vec3::new(0.0, 1.0, 0.0), |normal| self.normals[normal]));
+            mesh.missing_normals |= normal.is_none();
+            (mesh.vertices.len() - 1) as u32
+        })
     }
-}
\ No newline at end of file
+}
+
+pub(crate) fn parse_error(path: &Path, line: usize, message: &str) -> ObjError {
+    ObjError::Parse { path: path.to_path_buf(), line, message: message.to_string() }
+}
+
+// Lee al menos `min` números; los que sobran (como la w de "v") se conservan pero no se usan
+pub(crate) fn parse_floats(args: &[&str], min: usize, path: &Path, line: usize) -> Result<Vec<f32>, ObjError> {
+    if args.len() < min {
+        return Err(parse_error(path, line, &format!("expected {} numbers, found {}", min, args.len())));
+    }
+    args.iter()
+        .map(|arg| arg.parse::<f32>().map_err(|_| parse_error(path, line, &format!("invalid number {:?}", arg))))
+        .collect()
+}
+
+// Ruta de `target` vista desde el directorio `base`, con / como separador. Sin raíz común (otra
+// unidad en Windows) queda la ruta absoluta
+fn relative_path(target: &Path, base: &Path) -> String {
+    // Se resuelven los enlaces y los `..`. Un MTL que falta se resuelve por su directorio
+    let absolute = |path: &Path| fs::canonicalize(path)
+        .or_else(|err| match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => fs::canonicalize(parent).map(|parent| parent.join(name)),
+            _ => Err(err),
+        })
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let (target, base) = (absolute(target), absolute(if base.as_os_str().is_empty() { Path::new(".") } else { base }));
+
+    let common = target.components().zip(base.components()).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return target.display().to_string();
+    }
+    let parents = base.components().skip(common).map(|_| "..".to_string());
+    let rest = target.components().skip(common).map(|component| component.as_os_str().to_string_lossy().into_owned());
+    parents.chain(rest).collect::<Vec<_>>().join("/")
+}
@@ -0,0 +1,147 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// Scene units per astronomical unit when bodies are laid out in system mode
+pub const AU_SCALE: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+  pub semi_major_axis: f32,       // AU for planets, scene units for moons
+  pub eccentricity: f32,
+  pub inclination: f32,           // radians, relative to the ecliptic
+  pub ascending_node: f32,        // radians, longitude of the ascending node
+  pub argument_of_periapsis: f32, // radians
+  pub mean_anomaly_at_epoch: f32, // radians at time zero (J2000)
+  pub period: f32,                // days per orbit
+}
+
+impl OrbitalElements {
+  pub fn mean_anomaly(&self, days: f32) -> f32 {
+    let turns = days / self.period + self.mean_anomaly_at_epoch / (2.0 * PI);
+    turns.rem_euclid(1.0) * 2.0 * PI
+  }
+
+  // Position relative to the focus, in scene coordinates (Y is ecliptic north)
+  pub fn position(&self, days: f32) -> Vec3 {
+    let e = self.eccentricity;
+    let eccentric_anomaly = solve_kepler(self.mean_anomaly(days), e);
+
+    // Position in the orbital plane, periapsis along +x
+    let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+    let px = self.semi_major_axis * (cos_e - e);
+    let py = self.semi_major_axis * (1.0 - e * e).sqrt() * sin_e;
+
+    let (sin_w, cos_w) = self.argument_of_periapsis.sin_cos();
+    let (sin_n, cos_n) = self.ascending_node.sin_cos();
+    let (sin_i, cos_i) = self.inclination.sin_cos();
+
+    // Rotate into ecliptic coordinates
+    let x = (cos_w * cos_n - sin_w * sin_n * cos_i) * px + (-sin_w * cos_n - cos_w * sin_n * cos_i) * py;
+    let y = (cos_w * sin_n + sin_w * cos_n * cos_i) * px + (-sin_w * sin_n + cos_w * cos_n * cos_i) * py;
+    let z = (sin_w * sin_i) * px + (cos_w * sin_i) * py;
+
+    // Ecliptic Z is up in the scene, ecliptic Y points towards -Z
+    Vec3::new(x, z, -y)
+  }
+
+  pub fn for_shader(shader: u8) -> Option<OrbitalElements> {
+    match shader {
+      1 => Some(EARTH),
+      2 => Some(MARS),
+      3 => Some(MERCURY),
+      4 => Some(SATURN),
+      5 => Some(JUPITER),
+      6 => Some(URANUS),
+      8 => Some(MOON),
+      _ => None,
+    }
+  }
+}
+
+// Solves M = E - e sin(E) for the eccentric anomaly with Newton's method
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+  let mut eccentric_anomaly = if eccentricity < 0.8 { mean_anomaly } else { PI };
+
+  for _ in 0..10 {
+    let f = eccentric_anomaly - eccentricity * eccentric_anomaly.sin() - mean_anomaly;
+    let step = f / (1.0 - eccentricity * eccentric_anomaly.cos());
+    eccentric_anomaly -= step;
+    if step.abs() < 1e-6 {
+      break;
+    }
+  }
+
+  eccentric_anomaly
+}
+
+// J2000 mean elements (Standish, JPL), periapsis and mean anomaly derived from ϖ and L
+pub const MERCURY: OrbitalElements = OrbitalElements {
+  semi_major_axis: 0.38709927,
+  eccentricity: 0.20563594,
+  inclination: 7.004979 * PI / 180.0,
+  ascending_node: 48.330765 * PI / 180.0,
+  argument_of_periapsis: 29.12703 * PI / 180.0,
+  mean_anomaly_at_epoch: 174.79253 * PI / 180.0,
+  period: 87.9691,
+};
+
+pub const EARTH: OrbitalElements = OrbitalElements {
+  semi_major_axis: 1.0000026,
+  eccentricity: 0.01671123,
+  inclination: -0.00001531 * PI / 180.0,
+  ascending_node: 0.0,
+  argument_of_periapsis: 102.93768 * PI / 180.0,
+  mean_anomaly_at_epoch: 357.5269 * PI / 180.0,
+  period: 365.256,
+};
+
+pub const MARS: OrbitalElements = OrbitalElements {
+  semi_major_axis: 1.5237104,
+  eccentricity: 0.0933941,
+  inclination: 1.8496914 * PI / 180.0,
+  ascending_node: 49.55954 * PI / 180.0,
+  argument_of_periapsis: 286.49683 * PI / 180.0,
+  mean_anomaly_at_epoch: 19.390198 * PI / 180.0,
+  period: 686.980,
+};
+
+pub const JUPITER: OrbitalElements = OrbitalElements {
+  semi_major_axis: 5.202887,
+  eccentricity: 0.04838624,
+  inclination: 1.304397 * PI / 180.0,
+  ascending_node: 100.47391 * PI / 180.0,
+  argument_of_periapsis: 274.25458 * PI / 180.0,
+  mean_anomaly_at_epoch: 19.667961 * PI / 180.0,
+  period: 4332.59,
+};
+
+pub const SATURN: OrbitalElements = OrbitalElements {
+  semi_major_axis: 9.536676,
+  eccentricity: 0.05386179,
+  inclination: 2.485992 * PI / 180.0,
+  ascending_node: 113.66242 * PI / 180.0,
+  argument_of_periapsis: 338.93646 * PI / 180.0,
+  mean_anomaly_at_epoch: 317.35538 * PI / 180.0,
+  period: 10759.22,
+};
+
+pub const URANUS: OrbitalElements = OrbitalElements {
+  semi_major_axis: 19.189165,
+  eccentricity: 0.04725744,
+  inclination: 0.77263784 * PI / 180.0,
+  ascending_node: 74.01692 * PI / 180.0,
+  argument_of_periapsis: 96.937355 * PI / 180.0,
+  mean_anomaly_at_epoch: 142.28383 * PI / 180.0,
+  period: 30688.5,
+};
+
+// Phobos-like moon around Mars, measured in planet radii instead of AU
+pub const MOON: OrbitalElements = OrbitalElements {
+  semi_major_axis: 2.5,
+  eccentricity: 0.0151,
+  inclination: 1.093 * PI / 180.0,
+  ascending_node: 0.0,
+  argument_of_periapsis: 0.0,
+  mean_anomaly_at_epoch: 0.0,
+  period: 0.31891,
+};
@@ -0,0 +1,141 @@
+pub fn gaussian_blur(buffer: &mut [u32], width: usize, height: usize, kernel_size: usize, sigma: f32) {
+    let gaussian_kernel = create_gaussian_kernel(kernel_size, sigma);
+    let kernel_sum: f32 = gaussian_kernel.iter().map(|&x| x as f32).sum();
+
+    // Aplicar horizontalmente
+    for y in 0..height {
+        let mut temp_row = vec![0u32; width];
+        for (x, pixel) in temp_row.iter_mut().enumerate() {
+            let mut filtered_pixel = 0f32;
+            for (k, &weight) in gaussian_kernel.iter().enumerate() {
+                let sample_x = x as i32 + k as i32 - (gaussian_kernel.len() / 2) as i32;
+                if sample_x >= 0 && sample_x < width as i32 {
+                    filtered_pixel += buffer[sample_x as usize + y * width] as f32 * weight as f32;
+                }
+            }
+            *pixel = (filtered_pixel / kernel_sum).round() as u32;
+        }
+        buffer[y * width..(y + 1) * width].copy_from_slice(&temp_row);
+    }
+
+    // Aplicar verticalmente
+    for x in 0..width {
+        let mut temp_col = vec![0u32; height];
+        for (y, pixel) in temp_col.iter_mut().enumerate() {
+            let mut filtered_pixel = 0f32;
+            for (k, &weight) in gaussian_kernel.iter().enumerate() {
+                let sample_y = y as i32 + k as i32 - (gaussian_kernel.len() / 2) as i32;
+                if sample_y >= 0 && sample_y < height as i32 {
+                    filtered_pixel += buffer[x + sample_y as usize * width] as f32 * weight as f32;
+                }
+            }
+            *pixel = (filtered_pixel / kernel_sum).round() as u32;
+        }
+        for (y, &pixel) in temp_col.iter().enumerate() {
+            buffer[x + y * width] = pixel;
+        }
+    }
+}
+
+// Crear un kernel Gaussiano dinámicamente
+fn create_gaussian_kernel(size: usize, sigma: f32) -> Vec<u32> {
+    let mut kernel = vec![0u32; size];
+    let mean = (size as f32 - 1.0) / 2.0;
+    let coefficient = 1.0 / (2.0 * std::f32::consts::PI * sigma * sigma).sqrt();
+
+    for (x, value) in kernel.iter_mut().enumerate() {
+        let exp_numerator = -((x as f32 - mean) * (x as f32 - mean)) / (2.0 * sigma * sigma);
+        let exp_value = (-exp_numerator).exp();
+        *value = (coefficient * exp_value * 255.0) as u32;
+    }
+
+    kernel
+}
+
+pub fn apply_bloom(original: &mut [u32], bloom: &[u32]) {
+    for i in 0..original.len() {
+        let original_color = original[i];
+        let bloom_intensity = bloom[i];
+        if bloom_intensity > 0 {
+            original[i] = blend_bloom(original_color, bloom_intensity);
+        }
+    }
+}
+
+fn blend_bloom(base_color: u32, bloom_intensity: u32) -> u32 {
+    // Factores para el tonemapping y la mezcla de bloom
+    let bloom_strength = 0.8;  // Ajusta esto para controlar la fuerza del efecto de bloom
+    let max_bloom_effect = 1.2;  // Este valor limita cuánto puede influir el bloom
+
+    let r = ((base_color >> 16) & 0xFF) as f32;
+    let g = ((base_color >> 8) & 0xFF) as f32;
+    let b = (base_color & 0xFF) as f32;
+    let bloom = bloom_intensity as f32 * bloom_strength;
+
+    // Calcular nueva intensidad de color con clamping para evitar saturación
+    let new_r = ((r + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
+    let new_g = ((g + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
+    let new_b = ((b + bloom).min(255.0 * max_bloom_effect)).min(255.0) as u32;
+
+    // Recomponer el color
+    (new_r << 16) | (new_g << 8) | new_b
+}
+
+// Escala la imagen a otra resolución. Al reducir promedia los píxeles que cubre cada píxel de destino;
+// al ampliar usa el vecino más cercano
+pub fn resample(buffer: &[u32], width: usize, height: usize, target_width: usize, target_height: usize) -> Vec<u32> {
+    let span = |target: usize, target_size: usize, size: usize| {
+        let start = target * size / target_size;
+        let end = ((target + 1) * size / target_size).max(start + 1).min(size);
+        start..end
+    };
+
+    let mut output = Vec::with_capacity(target_width * target_height);
+    for ty in 0..target_height {
+        let rows = span(ty, target_height, height);
+        for tx in 0..target_width {
+            let columns = span(tx, target_width, width);
+            let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+            for y in rows.clone() {
+                for &pixel in &buffer[y * width + columns.start..y * width + columns.end] {
+                    r += (pixel >> 16) & 0xFF;
+                    g += (pixel >> 8) & 0xFF;
+                    b += pixel & 0xFF;
+                }
+            }
+            let count = (rows.len() * columns.len()) as u32;
+            output.push(((r / count) << 16) | ((g / count) << 8) | (b / count));
+        }
+    }
+    output
+}
+
+// Amplía (o reduce) la imagen interpolando linealmente entre los cuatro píxeles más cercanos
+pub fn resample_bilinear(buffer: &[u32], width: usize, height: usize, target_width: usize, target_height: usize) -> Vec<u32> {
+    // Posición en la imagen de origen del centro de un píxel de destino
+    let source = |target: usize, target_size: usize, size: usize| {
+        let position = ((target as f32 + 0.5) * size as f32 / target_size as f32 - 0.5).clamp(0.0, (size - 1) as f32);
+        let start = position.floor() as usize;
+        (start, (start + 1).min(size - 1), position - start as f32)
+    };
+    let channel = |pixel: u32, shift: u32| ((pixel >> shift) & 0xFF) as f32;
+
+    let mut output = Vec::with_capacity(target_width * target_height);
+    for ty in 0..target_height {
+        let (y0, y1, fy) = source(ty, target_height, height);
+        for tx in 0..target_width {
+            let (x0, x1, fx) = source(tx, target_width, width);
+            let corners = [buffer[y0 * width + x0], buffer[y0 * width + x1], buffer[y1 * width + x0], buffer[y1 * width + x1]];
+
+            let mut pixel = 0;
+            for shift in [16, 8, 0] {
+                let top = channel(corners[0], shift) * (1.0 - fx) + channel(corners[1], shift) * fx;
+                let bottom = channel(corners[2], shift) * (1.0 - fx) + channel(corners[3], shift) * fx;
+                let value = top * (1.0 - fy) + bottom * fy;
+                pixel |= (value.round() as u32) << shift;
+            }
+            output.push(pixel);
+        }
+    }
+    output
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use nalgebra_glm::{Vec2, Vec3};
+use crate::mesh::position_key;
+use crate::obj::Mesh;
+
+// Generadores de geometría para no depender de archivos OBJ. Las esferas tienen radio 1 y
+// coordenadas de textura equirectangulares: u da la vuelta en longitud y v va del polo norte (0)
+// al polo sur (1). Las normales son exactas, no promediadas
+
+// Esfera de meridianos y paralelos. `segments` divide la longitud y `rings` la latitud
+pub fn uv_sphere(segments: u32, rings: u32) -> Mesh {
+    let (segments, rings) = (segments.max(3), rings.max(2));
+    let mut vertices = Vec::new();
+    let mut texcoords = Vec::new();
+    for ring in 0..=rings {
+        let theta = PI * ring as f32 / rings as f32;
+        for segment in 0..=segments {
+            // En los polos cada columna tiene su vértice, con la u del centro de su triángulo
+            let pole = ring == 0 || ring == rings;
+            let u = (segment as f32 + if pole { 0.5 } else { 0.0 }) / segments as f32;
+            let phi = 2.0 * PI * u;
+            vertices.push(Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin()));
+            texcoords.push(Vec2::new(u, ring as f32 / rings as f32));
+        }
+    }
+
+    let row = segments + 1;
+    let mut indices = Vec::new();
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = ring * row + segment;
+            let (b, c, d) = (a + row, a + row + 1, a + 1);
+            // Junto a los polos el cuadrilátero se reduce a un triángulo
+            if ring != 0 {
+                indices.extend([a, d, b]);
+            }
+            if ring != rings - 1 {
+                indices.extend([d, c, b]);
+            }
+        }
+    }
+
+    let normals = vertices.clone();
+    Mesh::from_buffers("UV sphere", vertices, normals, texcoords, indices)
+}
+
+// Icosaedro subdividido: cada paso parte cada triángulo en cuatro. Los triángulos son casi
+// iguales en toda la esfera, sin acumularse en los polos
+pub fn icosphere(subdivisions: u32) -> Mesh {
+    let t = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut positions: Vec<Vec3> = [
+        (-1.0, t, 0.0), (1.0, t, 0.0), (-1.0, -t, 0.0), (1.0, -t, 0.0),
+        (0.0, -1.0, t), (0.0, 1.0, t), (0.0, -1.0, -t), (0.0, 1.0, -t),
+        (t, 0.0, -1.0), (t, 0.0, 1.0), (-t, 0.0, -1.0), (-t, 0.0, 1.0),
+    ].iter().map(|&(x, y, z)| Vec3::new(x, y, z).normalize()).collect();
+    let mut triangles: Vec<[u32; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        // Cada arista se parte una sola vez aunque la compartan dos triángulos
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                positions.push(((positions[a as usize] + positions[b as usize]) / 2.0).normalize());
+                (positions.len() - 1) as u32
+            })
+        };
+        triangles = triangles.iter().flat_map(|&[a, b, c]| {
+            let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+            [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+        }).collect();
+    }
+
+    spherical_mesh("Icosphere", &positions, &triangles)
+}
+
+// Cubo de `resolution` x `resolution` cuadros por cara inflado hasta la esfera. La proyección
+// reparte el área casi por igual entre los cuadros
+pub fn cube_sphere(resolution: u32) -> Mesh {
+    let resolution = resolution.max(1);
+    let faces = [
+        (Vec3::x(), -Vec3::z(), Vec3::y()),
+        (-Vec3::x(), Vec3::z(), Vec3::y()),
+        (Vec3::y(), Vec3::x(), -Vec3::z()),
+        (-Vec3::y(), Vec3::x(), Vec3::z()),
+        (Vec3::z(), Vec3::x(), Vec3::y()),
+        (-Vec3::z(), -Vec3::x(), Vec3::y()),
+    ];
+
+    let mut positions = Vec::new();
+    let mut shared: HashMap<[u32; 3], u32> = HashMap::new();
+    let mut triangles = Vec::new();
+    for (normal, right, up) in faces {
+        let mut grid = Vec::new();
+        for j in 0..=resolution {
+            for i in 0..=resolution {
+                let s = 2.0 * i as f32 / resolution as f32 - 1.0;
+                let t = 2.0 * j as f32 / resolution as f32 - 1.0;
+                let point = to_sphere(normal + right * s + up * t);
+                // Los bordes de las caras se comparten
+                let index = *shared.entry(position_key(point)).or_insert_with(|| {
+                    positions.push(point);
+                    (positions.len() - 1) as u32
+                });
+                grid.push(index);
+            }
+        }
+
+        let row = resolution as usize + 1;
+        for j in 0..resolution as usize {
+            for i in 0..resolution as usize {
+                let a = grid[j * row + i];
+                let (b, c, d) = (grid[j * row + i + 1], grid[(j + 1) * row + i + 1], grid[(j + 1) * row + i]);
+                triangles.push([a, b, c]);
+                triangles.push([a, c, d]);
+            }
+        }
+    }
+
+    spherical_mesh("Cube sphere", &positions, &triangles)
+}
+
+// Corona plana en el plano xz, mirando hacia +y. u da la vuelta y v va del borde interior (0) al
+// exterior (1). Con `inner_radius` 0 es un disco
+pub fn annulus(inner_radius: f32, outer_radius: f32, segments: u32) -> Mesh {
+    let segments = segments.max(3);
+    let mut vertices = Vec::new();
+    let mut texcoords = Vec::new();
+    for segment in 0..=segments {
+        let u = segment as f32 / segments as f32;
+        let (sin, cos) = (2.0 * PI * u).sin_cos();
+        vertices.push(Vec3::new(cos, 0.0, sin) * inner_radius);
+        texcoords.push(Vec2::new(u, 0.0));
+        vertices.push(Vec3::new(cos, 0.0, sin) * outer_radius);
+        texcoords.push(Vec2::new(u, 1.0));
+    }
+
+    let mut indices = Vec::new();
+    for segment in 0..segments {
+        let inner = segment * 2;
+        let (outer, next_inner, next_outer) = (inner + 1, inner + 2, inner + 3);
+        indices.extend([inner, next_outer, outer]);
+        if inner_radius > 0.0 {
+            indices.extend([inner, next_inner, next_outer]);
+        }
+    }
+
+    let normals = vec![Vec3::y(); vertices.len()];
+    Mesh::from_buffers("Annulus", vertices, normals, texcoords, indices)
+}
+
+// Uno de los generadores de esferas con su resolución, para elegir el teselado de cada cuerpo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphereGenerator {
+    Uv { segments: u32, rings: u32 },
+    Icosphere(u32),
+    CubeSphere(u32),
+}
+
+impl SphereGenerator {
+    pub fn mesh(&self) -> Mesh {
+        match *self {
+            SphereGenerator::Uv { segments, rings } => uv_sphere(segments, rings),
+            SphereGenerator::Icosphere(subdivisions) => icosphere(subdivisions),
+            SphereGenerator::CubeSphere(resolution) => cube_sphere(resolution),
+        }
+    }
+}
+
+// Lleva un punto de la superficie del cubo [-1, 1]³ a la esfera unitaria
+fn to_sphere(p: Vec3) -> Vec3 {
+    let (x2, y2, z2) = (p.x * p.x, p.y * p.y, p.z * p.z);
+    Vec3::new(
+        p.x * (1.0 - y2 / 2.0 - z2 / 2.0 + y2 * z2 / 3.0).sqrt(),
+        p.y * (1.0 - z2 / 2.0 - x2 / 2.0 + z2 * x2 / 3.0).sqrt(),
+        p.z * (1.0 - x2 / 2.0 - y2 / 2.0 + x2 * y2 / 3.0).sqrt(),
+    )
+}
+
+// Arma el mesh de una esfera unitaria con coordenadas de textura equirectangulares. Los triángulos
+// que cruzan la costura se desdoblan con u > 1 y los vértices de los polos toman la u del
+// triángulo, así que esos vértices se duplican
+fn spherical_mesh(name: &str, positions: &[Vec3], triangles: &[[u32; 3]]) -> Mesh {
+    let mut vertices = Vec::new();
+    let mut texcoords = Vec::new();
+    let mut indices = Vec::new();
+    let mut welded: HashMap<(u32, [u32; 2]), u32> = HashMap::new();
+
+    for triangle in triangles {
+        let corners = triangle.map(|index| positions[index as usize]);
+        let is_pole = |p: &Vec3| p.x * p.x + p.z * p.z < 1e-10;
+        let mut u = corners.map(|p| {
+            let u = p.z.atan2(p.x) / (2.0 * PI);
+            if u < 0.0 { u + 1.0 } else { u }
+        });
+
+        let off_pole = || (0..3).filter(|&k| !is_pole(&corners[k]));
+        let min = off_pole().map(|k| u[k]).fold(f32::INFINITY, f32::min);
+        let max = off_pole().map(|k| u[k]).fold(f32::NEG_INFINITY, f32::max);
+        if max - min > 0.5 {
+            for k in off_pole() {
+                if u[k] < 0.5 {
+                    u[k] += 1.0;
+                }
+            }
+        }
+        let (sum, count) = off_pole().fold((0.0, 0), |(sum, count), k| (sum + u[k], count + 1));
+        for k in 0..3 {
+            if is_pole(&corners[k]) && count > 0 {
+                u[k] = sum / count as f32;
+            }
+        }
+
+        for k in 0..3 {
+            let texcoord = Vec2::new(u[k], corners[k].y.clamp(-1.0, 1.0).acos() / PI);
+            let key = (triangle[k], [texcoord.x.to_bits(), texcoord.y.to_bits()]);
+            let index = *welded.entry(key).or_insert_with(|| {
+                vertices.push(corners[k]);
+                texcoords.push(texcoord);
+                (vertices.len() - 1) as u32
+            });
+            indices.push(index);
+        }
+    }
+
+    let normals = vertices.clone();
+    Mesh::from_buffers(name, vertices, normals, texcoords, indices)
+}
@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Contadores y tiempos de una llamada a `render` (un objeto)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    pub vertex: Duration,
+    pub raster: Duration,   // ensamblado de primitivas y rasterización
+    pub fragment: Duration, // prueba de profundidad, fragment shader y escritura
+    pub vertices: usize, // llamadas al vertex shader
+    pub triangles: usize,
+    pub fragments: usize,      // generados por la rasterización
+    pub shaded: usize,         // pasaron la prueba de profundidad y se sombrearon
+    pub depth_rejected: usize, // descartados antes de sombrear por estar detrás de otro
+    pub clipped: usize,        // fuera del framebuffer o del rango de profundidad
+}
+
+impl RenderStats {
+    pub fn total(&self) -> Duration {
+        self.vertex + self.raster + self.fragment
+    }
+
+    pub fn add(&mut self, other: &RenderStats) {
+        self.vertex += other.vertex;
+        self.raster += other.raster;
+        self.fragment += other.fragment;
+        self.vertices += other.vertices;
+        self.triangles += other.triangles;
+        self.fragments += other.fragments;
+        self.shaded += other.shaded;
+        self.depth_rejected += other.depth_rejected;
+        self.clipped += other.clipped;
+    }
+}
+
+// Un objeto dibujado durante el cuadro
+#[derive(Debug, Clone)]
+pub struct ObjectProfile {
+    pub name: String,
+    pub lod: usize, // nivel de detalle elegido, 0 es el más detallado
+    pub start: Instant,
+    pub stats: RenderStats,
+}
+
+// Una etapa fuera de la geometría: desenfoque, bloom, escalado, presentación...
+#[derive(Debug, Clone)]
+pub struct StageProfile {
+    pub name: &'static str,
+    pub start: Instant,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct FrameProfile {
+    pub start: Instant,
+    pub objects: Vec<ObjectProfile>,
+    pub stages: Vec<StageProfile>,
+}
+
+impl FrameProfile {
+    pub fn new() -> Self {
+        FrameProfile { start: Instant::now(), objects: Vec::new(), stages: Vec::new() }
+    }
+
+    pub fn add_object(&mut self, name: impl Into<String>, lod: usize, start: Instant, stats: RenderStats) {
+        self.objects.push(ObjectProfile { name: name.into(), lod, start, stats });
+    }
+
+    pub fn add_stage(&mut self, name: &'static str, start: Instant, duration: Duration) {
+        self.stages.push(StageProfile { name, start, duration });
+    }
+
+    // Mide `work` como una etapa con nombre
+    pub fn time_stage<T>(&mut self, name: &'static str, work: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = work();
+        self.add_stage(name, start, start.elapsed());
+        result
+    }
+
+    // Suma de todos los objetos
+    pub fn geometry(&self) -> RenderStats {
+        let mut total = RenderStats::default();
+        for object in &self.objects {
+            total.add(&object.stats);
+        }
+        total
+    }
+
+    // Tiempo total de las etapas con ese nombre
+    pub fn stage(&self, name: &str) -> Duration {
+        self.stages.iter().filter(|stage| stage.name == name).map(|stage| stage.duration).sum()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.geometry().total() + self.stages.iter().map(|stage| stage.duration).sum::<Duration>()
+    }
+
+    // Tiempo de reloj desde el inicio del cuadro hasta que terminó lo último medido
+    pub fn span(&self) -> Duration {
+        let object_ends = self.objects.iter().map(|object| object.start + object.stats.total());
+        let stage_ends = self.stages.iter().map(|stage| stage.start + stage.duration);
+        object_ends.chain(stage_ends)
+            .map(|end| end.saturating_duration_since(self.start))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl Default for FrameProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Etapas fuera de la geometría que aparecen como columnas del CSV
+const CSV_STAGES: [&str; 4] = ["blur", "bloom", "upscale", "present"];
+
+// Guarda los perfiles de varios cuadros seguidos para analizarlos después
+#[derive(Default)]
+pub struct Profiler {
+    frames: Vec<FrameProfile>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler { frames: Vec::new() }
+    }
+
+    pub fn record(&mut self, frame: FrameProfile) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[FrameProfile] {
+        &self.frames
+    }
+
+    // Una fila por cuadro, con tiempos en milisegundos
+    pub fn write_csv(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "frame,total_ms,vertex_ms,raster_ms,fragment_ms")?;
+        for stage in CSV_STAGES {
+            write!(writer, ",{}_ms", stage)?;
+        }
+        writeln!(writer, ",objects,triangles,fragments,shaded,depth_rejected,clipped,vertices")?;
+
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        for (index, frame) in self.frames.iter().enumerate() {
+            let geometry = frame.geometry();
+            write!(
+                writer, "{},{:.3},{:.3},{:.3},{:.3}",
+                index, ms(frame.total()), ms(geometry.vertex), ms(geometry.raster), ms(geometry.fragment)
+            )?;
+            for stage in CSV_STAGES {
+                write!(writer, ",{:.3}", ms(frame.stage(stage)))?;
+            }
+            writeln!(
+                writer, ",{},{},{},{},{},{},{}",
+                frame.objects.len(), geometry.triangles, geometry.fragments,
+                geometry.shaded, geometry.depth_rejected, geometry.clipped, geometry.vertices
+            )?;
+        }
+        writer.flush()
+    }
+
+    // Formato de eventos de Chrome (chrome://tracing, Perfetto): cada cuadro, objeto y etapa es un
+    // evento completo con su inicio y duración en microsegundos
+    pub fn write_chrome_trace(&self, path: &Path) -> io::Result<()> {
+        let Some(epoch) = self.frames.first().map(|frame| frame.start) else {
+            return File::create(path)?.write_all(b"[]\n");
+        };
+        let micros = |instant: Instant| instant.saturating_duration_since(epoch).as_secs_f64() * 1e6;
+
+        let mut events = Vec::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            events.push(trace_event(&format!("frame {}", index), "frame", micros(frame.start), frame.span(), String::new()));
+
+            for object in &frame.objects {
+                let stats = &object.stats;
+                let args = format!(
+                    r#","args":{{"lod":{},"vertices":{},"triangles":{},"fragments":{},"shaded":{},"depth_rejected":{},"clipped":{}}}"#,
+                    object.lod, stats.vertices, stats.triangles, stats.fragments, stats.shaded, stats.depth_rejected, stats.clipped
+                );
+                let start = micros(object.start);
+                events.push(trace_event(&object.name, "object", start, stats.total(), args));
+
+                // Las etapas de un objeto van una detrás de otra
+                let vertex_end = start + stats.vertex.as_secs_f64() * 1e6;
+                let raster_end = vertex_end + stats.raster.as_secs_f64() * 1e6;
+                events.push(trace_event("vertex", "stage", start, stats.vertex, String::new()));
+                events.push(trace_event("raster", "stage", vertex_end, stats.raster, String::new()));
+                events.push(trace_event("fragment", "stage", raster_end, stats.fragment, String::new()));
+            }
+
+            for stage in &frame.stages {
+                events.push(trace_event(stage.name, "stage", micros(stage.start), stage.duration, String::new()));
+            }
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "[\n{}\n]", events.join(",\n"))?;
+        writer.flush()
+    }
+}
+
+fn trace_event(name: &str, category: &str, start: f64, duration: Duration, args: String) -> String {
+    format!(
+        r#"{{"name":"{}","cat":"{}","ph":"X","ts":{:.3},"dur":{:.3},"pid":1,"tid":1{}}}"#,
+        name.replace('"', "'"), category, start, duration.as_secs_f64() * 1e6, args
+    )
+}
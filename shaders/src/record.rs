@@ -0,0 +1,212 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::camera::Camera;
+use crate::camera_path::CameraPath;
+use crate::image_io::{save_png, to_rgb_bytes};
+use crate::scene::{render_headless, Scene};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    Png,
+    Gif,
+    Y4m,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordSettings {
+    pub format: RecordFormat,
+    pub output: PathBuf,
+    pub planet: u8,
+    pub start: f32,    // segundos de simulación
+    pub duration: f32, // segundos de simulación
+    pub fps: u32,
+    pub speed: f32,    // segundos de simulación por segundo de video
+    pub width: usize,
+    pub height: usize,
+    // Recorrido de cámara a seguir; reemplaza la cámara fija, el inicio, la duración y la velocidad
+    pub camera_path: Option<PathBuf>,
+}
+
+impl RecordSettings {
+    pub fn new(format: RecordFormat) -> Self {
+        let output = match format {
+            RecordFormat::Png => "frames",
+            RecordFormat::Gif => "recording.gif",
+            RecordFormat::Y4m => "recording.y4m",
+        };
+
+        RecordSettings {
+            format,
+            output: PathBuf::from(output),
+            planet: 1,
+            start: 0.0,
+            duration: 5.0,
+            fps: 30,
+            speed: 1.0,
+            width: 680,
+            height: 800,
+            camera_path: None,
+        }
+    }
+
+    // Lee las opciones de grabación de la línea de comandos, p. ej.
+    // --record gif --planet 5 --start 0 --duration 10 --fps 25 --speed 2 --size 340x400 --out jupiter.gif
+    // o bien --record png --path camera_path.txt para seguir un recorrido grabado en el visor
+    // Devuelve None si no se pidió grabar
+    pub fn from_args(args: &[String]) -> Result<Option<Self>, String> {
+        let Some(position) = args.iter().position(|arg| arg == "--record") else {
+            return Ok(None);
+        };
+
+        let format = match args.get(position + 1).map(String::as_str) {
+            Some("png") => RecordFormat::Png,
+            Some("gif") => RecordFormat::Gif,
+            Some("y4m") => RecordFormat::Y4m,
+            other => return Err(format!("unknown record format {:?}, expected png, gif or y4m", other.unwrap_or(""))),
+        };
+        let mut settings = RecordSettings::new(format);
+
+        let mut i = 0;
+        while i < args.len() {
+            let value = args.get(i + 1).map(String::as_str).unwrap_or("");
+            match args[i].as_str() {
+                "--out" => settings.output = PathBuf::from(value),
+                "--path" => settings.camera_path = Some(PathBuf::from(value)),
+                "--planet" => settings.planet = parse_value("--planet", value)?,
+                "--start" => settings.start = parse_value("--start", value)?,
+                "--duration" => settings.duration = parse_value("--duration", value)?,
+                "--fps" => settings.fps = parse_value("--fps", value)?,
+                "--speed" => settings.speed = parse_value("--speed", value)?,
+                "--size" => {
+                    let (width, height) = value.split_once('x').ok_or("--size expects WIDTHxHEIGHT")?;
+                    settings.width = parse_value("--size", width)?;
+                    settings.height = parse_value("--size", height)?;
+                }
+                "--record" => {}
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 2;
+        }
+
+        if settings.fps == 0 || settings.width == 0 || settings.height == 0 {
+            return Err("--fps and --size must be greater than zero".to_string());
+        }
+        if settings.format == RecordFormat::Gif && (settings.width > u16::MAX as usize || settings.height > u16::MAX as usize) {
+            return Err("GIF frames are limited to 65535x65535".to_string());
+        }
+
+        Ok(Some(settings))
+    }
+
+    pub fn frame_count(&self) -> usize {
+        (self.duration * self.fps as f32).round().max(1.0) as usize
+    }
+
+    pub fn frame_time(&self, frame: usize) -> f32 {
+        self.start + frame as f32 * self.speed / self.fps as f32
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value {:?} for {}", value, option))
+}
+
+// Renderiza la secuencia sin ventana y la escribe en el formato pedido. Devuelve el número de cuadros
+pub fn record(scene: &Scene, camera: &Camera, settings: &RecordSettings) -> io::Result<usize> {
+    let path = match &settings.camera_path {
+        Some(file) => Some(CameraPath::load(file)?),
+        None => None,
+    };
+    let frame_count = match &path {
+        Some(path) => (path.duration() * settings.fps as f32).round() as usize + 1,
+        None => settings.frame_count(),
+    };
+
+    let frames = (0..frame_count).map(|frame| {
+        let shot = path.as_ref().and_then(|path| path.sample(frame as f32 / settings.fps as f32));
+        let framebuffer = match shot {
+            Some((pose, time)) => {
+                let mut path_camera = Camera::new(pose.eye, pose.center, pose.up);
+                path_camera.fov = pose.fov;
+                render_headless(scene, &path_camera, settings.planet, time, settings.width, settings.height)
+            }
+            None => render_headless(scene, camera, settings.planet, settings.frame_time(frame), settings.width, settings.height),
+        };
+        framebuffer.buffer
+    });
+
+    match settings.format {
+        RecordFormat::Png => write_png_sequence(&settings.output, settings, frames),
+        RecordFormat::Gif => write_gif(&settings.output, settings, frames),
+        RecordFormat::Y4m => write_y4m(&settings.output, settings, frames),
+    }
+}
+
+pub fn write_png_sequence(directory: &Path, settings: &RecordSettings, frames: impl Iterator<Item = Vec<u32>>) -> io::Result<usize> {
+    fs::create_dir_all(directory)?;
+    let mut count = 0;
+    for (index, frame) in frames.enumerate() {
+        save_png(&directory.join(format!("frame_{:05}.png", index)), settings.width, settings.height, &frame)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+pub fn write_gif(path: &Path, settings: &RecordSettings, frames: impl Iterator<Item = Vec<u32>>) -> io::Result<usize> {
+    let (width, height) = (settings.width as u16, settings.height as u16);
+    let mut encoder = gif::Encoder::new(BufWriter::new(File::create(path)?), width, height, &[])
+        .map_err(io::Error::other)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+    // El GIF mide los retardos en centésimas de segundo
+    let delay = (100.0 / settings.fps as f32).round() as u16;
+    let mut count = 0;
+    for frame in frames {
+        // Cada cuadro lleva su propia paleta de 256 colores
+        let mut gif_frame = gif::Frame::from_rgb_speed(width, height, &to_rgb_bytes(&frame), 10);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+// YUV4MPEG2 sin compresión y sin submuestreo de color (4:4:4), legible por ffmpeg y mpv
+pub fn write_y4m(path: &Path, settings: &RecordSettings, frames: impl Iterator<Item = Vec<u32>>) -> io::Result<usize> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444", settings.width, settings.height, settings.fps)?;
+
+    let plane_size = settings.width * settings.height;
+    let mut planes = vec![0u8; plane_size * 3];
+    let mut count = 0;
+    for frame in frames {
+        for (i, &pixel) in frame.iter().enumerate() {
+            let (y, u, v) = rgb_to_yuv(pixel);
+            planes[i] = y;
+            planes[plane_size + i] = u;
+            planes[2 * plane_size + i] = v;
+        }
+        writer.write_all(b"FRAME\n")?;
+        writer.write_all(&planes)?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+// BT.601 con rango limitado, el que asumen los reproductores para Y4M
+fn rgb_to_yuv(pixel: u32) -> (u8, u8, u8) {
+    let r = ((pixel >> 16) & 0xFF) as f32;
+    let g = ((pixel >> 8) & 0xFF) as f32;
+    let b = (pixel & 0xFF) as f32;
+
+    let y = 16.0 + 0.257 * r + 0.504 * g + 0.098 * b;
+    let u = 128.0 - 0.148 * r - 0.291 * g + 0.439 * b;
+    let v = 128.0 + 0.439 * r - 0.368 * g - 0.071 * b;
+
+    (y.round() as u8, u.round() as u8, v.round() as u8)
+}
@@ -0,0 +1,118 @@
+use fastnoise_lite::FastNoiseLite;
+use nalgebra_glm::Vec3;
+use crate::noise::{create_noise, sample_sphere};
+use crate::vertex::{tangent_basis, Vertex};
+
+// Paso de las diferencias finitas, relativo a la distancia del punto al centro. Es del orden de
+// una arista de las esferas para que la normal siga la forma que el mesh puede mostrar y no los
+// detalles más finos del ruido, que entre vértice y vértice sólo serían ruido en la iluminación
+const GRADIENT_STEP: f32 = 0.03;
+// Paso para el detalle por fragmento, del orden de un píxel con el planeta llenando media ventana
+const BUMP_STEP: f32 = 0.004;
+
+// Relieve de un cuerpo: su mismo ruido, muestreado con `sample_sphere` igual que en su shader, sube o
+// baja la superficie a lo largo de la normal. La altura es relativa al radio, así que la misma
+// configuración sirve para un planeta y para su luna
+pub struct Relief {
+    noise: FastNoiseLite,
+    amplitude: f32,
+    // Cuánto del detalle fino llega a la iluminación: 1 es el relieve tal cual, 0 lo apaga
+    bump: f32,
+}
+
+impl Relief {
+    pub fn new(noise: FastNoiseLite, amplitude: f32) -> Self {
+        Relief { noise, amplitude, bump: 1.0 }
+    }
+
+    pub fn with_bump(mut self, bump: f32) -> Self {
+        self.bump = bump;
+        self
+    }
+
+    // Los cuerpos rocosos tienen relieve; los gaseosos, el Sol y los anillos quedan lisos
+    pub fn for_shader(shader: u8) -> Option<Self> {
+        let (amplitude, bump) = match shader {
+            2 => (0.03, 1.0), // Marte: cordilleras del ruido ridged
+            // Mercurio: cráteres del ruido ping-pong, cuyas octavas altas pesan tanto como las
+            // bajas y a escala de píxel sólo serían grano
+            3 => (0.02, 0.3),
+            8 => (0.04, 1.0), // Luna
+            _ => return None,
+        };
+        Some(Relief::new(create_noise(shader), amplitude).with_bump(bump))
+    }
+
+    // Cuánto puede salir la superficie, como fracción del radio
+    pub fn amplitude(&self) -> f32 {
+        self.amplitude
+    }
+
+    // Desplazamiento de la superficie en el punto, en las unidades del modelo
+    pub fn height(&self, point: &Vec3) -> f32 {
+        let radius = point.magnitude();
+        if radius == 0.0 {
+            return 0.0;
+        }
+        self.amplitude * radius * sample_sphere(&self.noise, point, 1.0)
+    }
+
+    // Mueve el vértice a lo largo de su normal. La normal nueva resta a la original el gradiente de
+    // la altura sobre la superficie y la base tangente se vuelve a hacer ortogonal conservando su
+    // orientación
+    pub fn displace(&self, vertex: &Vertex) -> Vertex {
+        if vertex.normal.magnitude_squared() == 0.0 {
+            return vertex.clone();
+        }
+        let normal = vertex.normal.normalize();
+        let height = self.height(&vertex.position);
+
+        let displaced_normal = (normal - self.slope(&vertex.position, &normal, GRADIENT_STEP)).normalize();
+
+        let mut displaced = vertex.clone();
+        displaced.position = vertex.position + normal * height;
+        displaced.normal = displaced_normal;
+
+        let tangent = vertex.tangent - displaced_normal * displaced_normal.dot(&vertex.tangent);
+        if tangent.magnitude_squared() > 1e-12 {
+            let tangent = tangent.normalize();
+            let handedness = if displaced_normal.cross(&tangent).dot(&vertex.bitangent) < 0.0 { -1.0 } else { 1.0 };
+            displaced.tangent = tangent;
+            displaced.bitangent = displaced_normal.cross(&tangent) * handedness;
+        } else {
+            (displaced.tangent, displaced.bitangent) = tangent_basis(&displaced_normal);
+        }
+        displaced
+    }
+
+    // Pendiente del detalle que el vertex shader no alcanza a mostrar: la medida a escala de píxel
+    // menos la que ya inclinó la normal de los vértices, multiplicada por la fuerza del bump.
+    // Restada a la normal interpolada da la normal del bump mapping, en el espacio del modelo.
+    // Corre por fragmento, así que usa diferencias hacia adelante que comparten la altura del centro
+    pub fn detail_slope(&self, point: &Vec3) -> Vec3 {
+        let radius = point.magnitude();
+        if radius == 0.0 || self.bump == 0.0 {
+            return Vec3::zeros();
+        }
+        let (u, v) = tangent_basis(&(point / radius));
+        let height = self.height(point);
+        let along = |direction: Vec3, step: f32| {
+            let step = step * radius;
+            (self.height(&(point + direction * step)) - height) / step
+        };
+        let detail = |direction: Vec3| along(direction, BUMP_STEP) - along(direction, GRADIENT_STEP);
+        (u * detail(u) + v * detail(v)) * self.bump
+    }
+
+    // Gradiente de la altura sobre el plano tangente, con diferencias centradas de `step` veces el radio
+    fn slope(&self, point: &Vec3, normal: &Vec3, step: f32) -> Vec3 {
+        let (u, v) = tangent_basis(normal);
+        let step = step * point.magnitude().max(1e-3);
+        let along = |direction: Vec3| {
+            let ahead = self.height(&(point + direction * step));
+            let behind = self.height(&(point - direction * step));
+            (ahead - behind) / (2.0 * step)
+        };
+        u * along(u) + v * along(v)
+    }
+}
@@ -0,0 +1,259 @@
+use nalgebra_glm::{Vec3, Vec4, Mat4, look_at, perspective, ortho, translation};
+use crate::camera::{Camera, Projection};
+use std::time::Instant;
+use crate::framebuffer::Framebuffer;
+use crate::profiler::RenderStats;
+use crate::mesh::IndexedMesh;
+use crate::vertex::Vertex;
+use crate::triangle::triangle;
+use crate::shaders::{vertex_shader, fragment_shader};
+use crate::Uniforms;
+
+pub fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+
+    let rotation_matrix_x = Mat4::new(
+        1.0,  0.0,    0.0,   0.0,
+        0.0,  cos_x, -sin_x, 0.0,
+        0.0,  sin_x,  cos_x, 0.0,
+        0.0,  0.0,    0.0,   1.0,
+    );
+
+    let rotation_matrix_y = Mat4::new(
+        cos_y,  0.0,  sin_y, 0.0,
+        0.0,    1.0,  0.0,   0.0,
+        -sin_y, 0.0,  cos_y, 0.0,
+        0.0,    0.0,  0.0,   1.0,
+    );
+
+    let rotation_matrix_z = Mat4::new(
+        cos_z, -sin_z, 0.0, 0.0,
+        sin_z,  cos_z, 0.0, 0.0,
+        0.0,    0.0,  1.0, 0.0,
+        0.0,    0.0,  0.0, 1.0,
+    );
+
+    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
+
+    let transform_matrix = Mat4::new(
+        scale, 0.0,   0.0,   translation.x,
+        0.0,   scale, 0.0,   translation.y,
+        0.0,   0.0,   scale, translation.z,
+        0.0,   0.0,   0.0,   1.0,
+    );
+
+    transform_matrix * rotation_matrix
+}
+
+
+pub fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
+    look_at(&eye, &center, &up)
+}
+
+// Proyección de la cámara con los planos de recorte dados: perspectiva u ortográfica, y con el
+// centro óptico desplazado según `lens_shift` (frustum asimétrico)
+pub fn create_projection_matrix(camera: &Camera, width: f32, height: f32, near: f32, far: f32) -> Mat4 {
+    let aspect_ratio = width / height;
+    let projection = match camera.projection {
+        Projection::Perspective => perspective(aspect_ratio, camera.fov, near, far),
+        Projection::Orthographic => {
+            let half_height = camera.distance() * (camera.fov / 2.0).tan();
+            let half_width = half_height * aspect_ratio;
+            ortho(-half_width, half_width, -half_height, half_height, near, far)
+        }
+    };
+
+    // Trasladar en coordenadas normalizadas equivale a mover la ventana del frustum
+    translation(&Vec3::new(camera.lens_shift.x, camera.lens_shift.y, 0.0)) * projection
+}
+
+// Rayo (origen, dirección unitaria) que pasa por el píxel (x, y). Usa las mismas matrices que el
+// renderizado para que el rayo coincida con lo que se ve. En ortográfica los rayos son paralelos
+pub fn screen_ray(camera: &Camera, x: f32, y: f32, width: f32, height: f32) -> (Vec3, Vec3) {
+    let view = create_view_matrix(camera.eye, camera.center, camera.up);
+    let projection = create_projection_matrix(camera, width, height, 0.1, 1000.0);
+    let inverse = (projection * view).try_inverse().unwrap_or_else(Mat4::identity);
+
+    // El viewport invierte el eje y
+    let ndc_x = 2.0 * x / width - 1.0;
+    let ndc_y = 1.0 - 2.0 * y / height;
+    let unproject = |z: f32| {
+        let point = inverse * Vec4::new(ndc_x, ndc_y, z, 1.0);
+        Vec3::new(point.x, point.y, point.z) / point.w
+    };
+    let (near, far) = (unproject(-1.0), unproject(1.0));
+
+    (near, (far - near).normalize())
+}
+
+// Radio en píxeles con que se ve una esfera, para elegir el nivel de detalle. Si la cámara está
+// dentro de la esfera devuelve infinito
+pub fn projected_radius(camera: &Camera, center: Vec3, radius: f32, viewport_height: f32) -> f32 {
+    let half_height = match camera.projection {
+        Projection::Perspective => {
+            let distance = (center - camera.eye).magnitude();
+            if distance <= radius {
+                return f32::INFINITY;
+            }
+            // Mitad del alto visible a esa distancia
+            distance * (camera.fov / 2.0).tan()
+        }
+        Projection::Orthographic => camera.distance() * (camera.fov / 2.0).tan(),
+    };
+    radius / half_height * viewport_height / 2.0
+}
+
+pub fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
+    Mat4::new(
+        width / 2.0, 0.0, 0.0, width / 2.0,
+        0.0, -height / 2.0, 0.0, height / 2.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0
+    )
+}
+
+// Dibuja un objeto dado como lista plana, tres vértices por triángulo, y devuelve lo que costó cada etapa
+pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], time: f32) -> RenderStats {
+    let mut stats = RenderStats::default();
+
+    // Vertex Shader Stage
+    let start = Instant::now();
+    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
+    for vertex in vertex_array {
+        let transformed = vertex_shader(vertex, uniforms);
+        transformed_vertices.push(transformed);
+    }
+    stats.vertex = start.elapsed();
+    stats.vertices = transformed_vertices.len();
+
+    // Primitive Assembly Stage
+    let triangles = transformed_vertices.chunks_exact(3).map(|tri| [&tri[0], &tri[1], &tri[2]]);
+    rasterize(framebuffer, uniforms, triangles, time, &mut stats);
+    stats
+}
+
+// Igual que `render`, pero con un buffer de vértices y otro de índices. Cada vértice pasa una sola
+// vez por el vertex shader y los triángulos que lo comparten reutilizan el resultado
+pub fn draw_indexed(framebuffer: &mut Framebuffer, uniforms: &Uniforms, mesh: &IndexedMesh, time: f32) -> RenderStats {
+    let mut stats = RenderStats::default();
+
+    // Vertex Shader Stage, sólo para los vértices que usa algún triángulo
+    let start = Instant::now();
+    let mut cache: Vec<Option<Vertex>> = vec![None; mesh.vertices.len()];
+    for &index in &mesh.indices {
+        let slot = &mut cache[index as usize];
+        if slot.is_none() {
+            *slot = Some(vertex_shader(&mesh.vertices[index as usize], uniforms));
+            stats.vertices += 1;
+        }
+    }
+    stats.vertex = start.elapsed();
+
+    // Primitive Assembly Stage
+    let triangles = mesh.indices.chunks_exact(3).map(|tri| {
+        let vertex = |i: usize| cache[tri[i] as usize].as_ref().unwrap();
+        [vertex(0), vertex(1), vertex(2)]
+    });
+    rasterize(framebuffer, uniforms, triangles, time, &mut stats);
+    stats
+}
+
+// Rasterización y sombreado, compartidos por los dos caminos
+fn rasterize<'a>(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    triangles: impl Iterator<Item = [&'a Vertex; 3]>,
+    time: f32,
+    stats: &mut RenderStats,
+) {
+    // Rasterization Stage
+    let start = Instant::now();
+    let (width, height) = (framebuffer.width, framebuffer.height);
+    let mut fragments = Vec::new();
+    for tri in triangles {
+        stats.triangles += 1;
+        let in_front = tri.map(in_front_of_near);
+        if in_front.iter().all(|&inside| inside) {
+            fragments.extend(triangle(tri[0], tri[1], tri[2], width, height));
+        } else if in_front.iter().any(|&inside| inside) {
+            for [a, b, c] in clip_near(tri, &uniforms.viewport_matrix) {
+                fragments.extend(triangle(&a, &b, &c, width, height));
+            }
+        }
+    }
+    stats.raster = start.elapsed();
+    stats.fragments = fragments.len();
+
+    // Fragment Processing Stage
+    let start = Instant::now();
+    for fragment in fragments {
+        let x = fragment.position.x as usize;
+        let y = fragment.position.y as usize;
+        // Lo que queda más allá del plano lejano, o fuera de la pantalla, no se dibuja
+        if x >= framebuffer.width || y >= framebuffer.height || !(-1.0..=1.0).contains(&fragment.depth) {
+            stats.clipped += 1;
+            continue;
+        }
+
+        // Prueba de profundidad temprana: no vale la pena sombrear lo que va a quedar tapado
+        if !framebuffer.depth_test(x, y, fragment.depth) {
+            stats.depth_rejected += 1;
+            continue;
+        }
+
+        // Apply fragment shader
+        let (shaded_color, emission) = fragment_shader(&fragment, uniforms, time);
+        let color = shaded_color.to_hex();
+        framebuffer.set_current_color(color);
+        framebuffer.point(x, y, fragment.depth, emission);
+        stats.shaded += 1;
+    }
+    stats.fragment = start.elapsed();
+}
+
+// Un vértice está delante del plano cercano cuando en el espacio de recorte z >= -w
+fn in_front_of_near(vertex: &Vertex) -> bool {
+    vertex.clip_position.z + vertex.clip_position.w >= 0.0
+}
+
+// Recorta contra el plano cercano un triángulo que lo cruza, antes de que la división por w
+// refleje en la pantalla lo que está detrás de la cámara. Quedan uno o dos triángulos
+fn clip_near(tri: [&Vertex; 3], viewport: &Mat4) -> Vec<[Vertex; 3]> {
+    let distance = |vertex: &Vertex| vertex.clip_position.z + vertex.clip_position.w;
+    let mut polygon = Vec::with_capacity(4);
+    for k in 0..3 {
+        let (a, b) = (tri[k], tri[(k + 1) % 3]);
+        let (distance_a, distance_b) = (distance(a), distance(b));
+        if distance_a >= 0.0 {
+            polygon.push(a.clone());
+        }
+        if (distance_a >= 0.0) != (distance_b >= 0.0) {
+            polygon.push(lerp_vertex(a, b, distance_a / (distance_a - distance_b), viewport));
+        }
+    }
+    (1..polygon.len().saturating_sub(1))
+        .map(|k| [polygon[0].clone(), polygon[k].clone(), polygon[k + 1].clone()])
+        .collect()
+}
+
+// Vértice en el punto `t` de la arista de `a` a `b`, con su posición en pantalla recalculada
+fn lerp_vertex(a: &Vertex, b: &Vertex, t: f32, viewport: &Mat4) -> Vertex {
+    let clip = a.clip_position.lerp(&b.clip_position, t);
+    let screen = viewport * Vec4::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w, 1.0);
+    Vertex {
+        position: a.position.lerp(&b.position, t),
+        normal: a.normal.lerp(&b.normal, t),
+        tangent: a.tangent.lerp(&b.tangent, t),
+        bitangent: a.bitangent.lerp(&b.bitangent, t),
+        tex_coords: a.tex_coords.lerp(&b.tex_coords, t),
+        color: a.color.lerp(&b.color, t),
+        transformed_position: Vec3::new(screen.x, screen.y, screen.z),
+        clip_position: clip,
+        transformed_normal: a.transformed_normal.lerp(&b.transformed_normal, t),
+        transformed_tangent: a.transformed_tangent.lerp(&b.transformed_tangent, t),
+        transformed_bitangent: a.transformed_bitangent.lerp(&b.transformed_bitangent, t),
+        world_position: a.world_position.lerp(&b.world_position, t),
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+// Weight of the newest frame in the smoothed frame time
+const SMOOTHING: f32 = 0.1;
+// Frame times within this fraction of the target leave the scale alone
+const TOLERANCE: f32 = 0.1;
+// Largest relative scale change per adjustment, so one slow frame cannot halve the resolution
+const MAX_STEP: f32 = 0.1;
+// Scales are rounded to this step to avoid reallocating the framebuffer for tiny changes
+const QUANTUM: f32 = 0.05;
+// Frames to wait after a change before measuring again, letting the smoothed time settle
+const COOLDOWN_FRAMES: u32 = 10;
+
+// Picks the internal render scale that keeps frame time near a target
+pub struct DynamicResolution {
+  enabled: bool,
+  target_frame_time: f32,
+  min_scale: f32,
+  max_scale: f32,
+  scale: f32,
+  smoothed_frame_time: Option<f32>,
+  cooldown: u32,
+}
+
+impl DynamicResolution {
+  pub fn new(target_fps: f32, min_scale: f32, max_scale: f32) -> Self {
+    DynamicResolution {
+      enabled: true,
+      target_frame_time: 1.0 / target_fps,
+      min_scale,
+      max_scale,
+      scale: max_scale,
+      smoothed_frame_time: None,
+      cooldown: 0,
+    }
+  }
+
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn scale(&self) -> f32 {
+    self.scale
+  }
+
+  pub fn target_fps(&self) -> f32 {
+    1.0 / self.target_frame_time
+  }
+
+  // Smoothed time of the measured work per frame, if any frame has been measured
+  pub fn frame_time(&self) -> Option<f32> {
+    self.smoothed_frame_time
+  }
+
+  // Sets the scale by hand. Outside the configured bounds it only sticks while dynamic scaling is off
+  pub fn set_scale(&mut self, scale: f32) {
+    self.scale = scale;
+    self.smoothed_frame_time = None;
+    self.cooldown = COOLDOWN_FRAMES;
+  }
+
+  pub fn set_enabled(&mut self, enabled: bool) {
+    self.enabled = enabled;
+    if enabled {
+      self.set_scale(self.scale.clamp(self.min_scale, self.max_scale));
+    }
+  }
+
+  // Feeds the time the last frame took and returns the scale to render the next one at
+  pub fn update(&mut self, frame_time: Duration) -> f32 {
+    let frame_time = frame_time.as_secs_f32();
+    let smoothed = match self.smoothed_frame_time {
+      Some(smoothed) => smoothed + (frame_time - smoothed) * SMOOTHING,
+      None => frame_time,
+    };
+    self.smoothed_frame_time = Some(smoothed);
+
+    if !self.enabled {
+      return self.scale;
+    }
+    if self.cooldown > 0 {
+      self.cooldown -= 1;
+      return self.scale;
+    }
+
+    let ratio = self.target_frame_time / smoothed;
+    if (1.0 - TOLERANCE..=1.0 + TOLERANCE).contains(&ratio) {
+      return self.scale;
+    }
+
+    // Render cost grows with the pixel count, that is with the square of the scale
+    let wanted = self.scale * ratio.sqrt().clamp(1.0 - MAX_STEP, 1.0 + MAX_STEP);
+    let quantized = ((wanted / QUANTUM).round() * QUANTUM).clamp(self.min_scale, self.max_scale);
+    if quantized != self.scale {
+      self.scale = quantized;
+      // Frames rendered at the old scale no longer say anything about the new one
+      self.smoothed_frame_time = None;
+      self.cooldown = COOLDOWN_FRAMES;
+    }
+    self.scale
+  }
+}
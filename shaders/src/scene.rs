@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use nalgebra_glm::{Vec3, Mat4};
+use crate::body::{self, Body, DAYS_PER_SECOND};
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::mesh::{IndexedMesh, LodMesh};
+use crate::obj::{Mesh, Obj, ObjError};
+use crate::orbit::{self, OrbitalElements, AU_SCALE};
+use crate::primitives::{annulus, icosphere, SphereGenerator};
+use crate::postprocess::{gaussian_blur, apply_bloom};
+use crate::profiler::FrameProfile;
+use crate::relief::Relief;
+use crate::render::{create_model_matrix, create_view_matrix, create_projection_matrix, create_viewport_matrix, draw_indexed, projected_radius};
+use crate::Uniforms;
+
+pub const BACKGROUND_COLOR: u32 = 0x333355;
+
+// Planetas del modo sistema: (shader, escala de dibujo). Las distancias siguen sus órbitas reales
+const SYSTEM_PLANETS: [(u8, f32); 6] = [(3, 0.25), (1, 0.4), (2, 0.3), (5, 1.2), (4, 1.0), (6, 0.7)];
+const SYSTEM_SUN_SCALE: f32 = 2.0;
+
+// Niveles de detalle de las esferas: (subdivisiones de la icoesfera, radio en píxeles por debajo
+// del cual se usa). Con los modelos cargados el nivel más detallado es el archivo
+const LOADED_SPHERE_LODS: [(u32, f32); 2] = [(2, 12.0), (1, 5.0)];
+const LOADED_MOON_LODS: [(u32, f32); 1] = [(1, 5.0)];
+const PROCEDURAL_SPHERE_LODS: [(u32, f32); 3] = [(3, 40.0), (2, 16.0), (1, 6.0)];
+
+// Teselado de `Scene::procedural`. El hueco de los anillos deja libre el planeta, que con la
+// escala de los anillos mide 1 / 1.5 de su radio
+const PROCEDURAL_SPHERE_SUBDIVISIONS: u32 = 4;
+const PROCEDURAL_MOON_SUBDIVISIONS: u32 = 3;
+const MOON_RADIUS: f32 = 0.5;
+const PROCEDURAL_RING_INNER_RADIUS: f32 = 0.75;
+const PROCEDURAL_RING_SEGMENTS: u32 = 96;
+
+// Límites del ajuste automático de los planos de recorte
+const MIN_NEAR: f32 = 0.01;
+const MAX_DEPTH_RATIO: f32 = 100_000.0;
+
+pub struct Scene {
+    sphere: LodMesh,
+    moon: LodMesh,
+    ring: LodMesh,
+    // Esferas propias de algunos planetas, por shader. Los demás usan `sphere`
+    spheres: HashMap<u8, LodMesh>,
+}
+
+// Nivel de detalle que usó cada objeto en el último cuadro, para la histéresis. Lo guarda quien
+// dibuja cuadro tras cuadro; con uno vacío cada objeto elige su nivel sólo por su tamaño en pantalla
+#[derive(Debug, Clone, Default)]
+pub struct LodState {
+    levels: HashMap<&'static str, usize>,
+}
+
+impl LodState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Nivel con que se dibujó el objeto la última vez
+    pub fn level(&self, name: &str) -> Option<usize> {
+        self.levels.get(name).copied()
+    }
+}
+
+// Lo que comparten todos los objetos de un cuadro
+struct FrameContext<'a> {
+    camera: &'a Camera,
+    time: f32,
+    lod: &'a mut LodState,
+    profile: FrameProfile,
+}
+
+// Un cuerpo ya colocado en la escena, con la esfera que lo envuelve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedBody {
+    pub shader: u8,
+    pub position: Vec3,
+    pub radius: f32,
+}
+
+// Un objeto de la escena: el mesh que le toca por su shader, ya colocado con su matriz de modelo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneObject {
+    pub name: &'static str,
+    pub shader: u8,
+    pub model_matrix: Mat4,
+}
+
+impl SceneObject {
+    // Uniforms con que se dibuja: el ruido y el relieve son siempre los de su propio shader
+    pub fn uniforms(&self) -> Uniforms {
+        Uniforms::for_shader(self.shader, self.model_matrix)
+    }
+}
+
+impl Scene {
+    pub fn load() -> Result<Self, ObjError> {
+        let obj = Obj::load("assets/models/sphere.obj")?;
+        let moon = Obj::load("assets/models/moon.obj")?;
+        let ring_obj = Obj::load("assets/models/ring.obj")?;
+
+        Ok(Scene::from_meshes(
+            sphere_lods(obj.get_indexed_mesh(), 1.0, &LOADED_SPHERE_LODS),
+            sphere_lods(moon.get_indexed_mesh(), MOON_RADIUS, &LOADED_MOON_LODS),
+            LodMesh::new(ring_obj.get_indexed_mesh()),
+        ))
+    }
+
+    // La misma escena con geometría generada, sin leer archivos
+    pub fn procedural() -> Self {
+        Scene::procedural_with(|shader| match shader {
+            8 => SphereGenerator::Icosphere(PROCEDURAL_MOON_SUBDIVISIONS),
+            _ => SphereGenerator::Icosphere(PROCEDURAL_SPHERE_SUBDIVISIONS),
+        })
+    }
+
+    // Escena generada en la que `generator` da la esfera de cada cuerpo según su shader, luna
+    // incluida. Los niveles de detalle más bajos siguen siendo icoesferas
+    pub fn procedural_with(generator: impl Fn(u8) -> SphereGenerator) -> Self {
+        let default = SphereGenerator::Icosphere(PROCEDURAL_SPHERE_SUBDIVISIONS);
+        let mut scene = Scene::from_meshes(
+            sphere_lods(scaled_mesh(default.mesh(), 1.0), 1.0, &PROCEDURAL_SPHERE_LODS),
+            sphere_lods(scaled_mesh(generator(8).mesh(), MOON_RADIUS), MOON_RADIUS, &PROCEDURAL_SPHERE_LODS[1..]),
+            LodMesh::new(annulus(PROCEDURAL_RING_INNER_RADIUS, 1.0, PROCEDURAL_RING_SEGMENTS).get_indexed_mesh()),
+        );
+        for shader in 1..=7 {
+            let sphere = generator(shader);
+            if sphere != default {
+                scene = scene.with_body_mesh(shader, sphere_lods(scaled_mesh(sphere.mesh(), 1.0), 1.0, &PROCEDURAL_SPHERE_LODS));
+            }
+        }
+        scene
+    }
+
+    // Los meshes van centrados en el origen y se dibujan con las escalas de cada cuerpo: el
+    // planeta mide 1 de radio como sphere.obj, la luna 0.5 y los anillos 1, en el plano xz
+    pub fn from_meshes(sphere: LodMesh, moon: LodMesh, ring: LodMesh) -> Self {
+        Scene { sphere, moon, ring, spheres: HashMap::new() }
+    }
+
+    // Cambia el mesh de un cuerpo, según su shader: la luna (8), los anillos (9) o la esfera de un planeta
+    pub fn with_body_mesh(mut self, shader: u8, mesh: LodMesh) -> Self {
+        match shader {
+            8 => self.moon = mesh,
+            9 => self.ring = mesh,
+            _ => {
+                self.spheres.insert(shader, mesh);
+            }
+        }
+        self
+    }
+
+    // Mesh con que se dibuja el objeto del shader dado
+    pub fn body_mesh(&self, shader: u8) -> &LodMesh {
+        match shader {
+            8 => &self.moon,
+            9 => &self.ring,
+            _ => self.spheres.get(&shader).unwrap_or(&self.sphere),
+        }
+    }
+
+    // Cuerpos visibles en un cuadro, en las mismas posiciones en que los dibuja `render`.
+    // El radio de Saturno incluye sus anillos
+    pub fn bodies(&self, planet: u8, time: f32) -> Vec<PlacedBody> {
+        let days = time * DAYS_PER_SECOND;
+        let mut bodies = Vec::new();
+
+        let mut place = |shader: u8, position: Vec3, scale: f32| {
+            let radius = match shader {
+                4 => self.body_mesh(4).radius().max(self.ring.radius() * 1.5),
+                _ => self.body_mesh(shader).radius(),
+            };
+            // Las montañas del relieve también cuentan para el radio
+            let relief = Relief::for_shader(shader).map_or(0.0, |relief| relief.amplitude());
+            bodies.push(PlacedBody { shader, position, radius: radius * (1.0 + relief) * scale });
+        };
+
+        if planet == 0 {
+            place(7, Vec3::new(0.0, 0.0, 0.0), SYSTEM_SUN_SCALE);
+            for (shader, scale) in SYSTEM_PLANETS {
+                let position = OrbitalElements::for_shader(shader).unwrap().position(days) * AU_SCALE;
+                place(shader, position, scale);
+                if shader == 2 {
+                    place(8, position + orbit::MOON.position(days) * scale, scale * 0.5);
+                }
+            }
+        } else {
+            place(planet, Vec3::new(0.0, 0.0, 0.0), 1.0);
+            if planet == 2 {
+                place(8, orbit::MOON.position(days), 0.5);
+            }
+        }
+
+        bodies
+    }
+
+    // Efectos de posprocesado que aplica `render` para el planeta dado
+    pub fn post_effects(&self, planet: u8) -> Vec<&'static str> {
+        if planet == 0 || planet == 7 { vec!["sun glow"] } else { Vec::new() }
+    }
+
+    // Planos de recorte ajustados a los cuerpos que hay delante de la cámara, para aprovechar
+    // la precisión del buffer de profundidad
+    pub fn clip_range(&self, camera: &Camera, planet: u8, time: f32) -> (f32, f32) {
+        let forward = camera.forward();
+        let (mut near, mut far) = (f32::INFINITY, 0.0f32);
+        for body in self.bodies(planet, time) {
+            let depth = (body.position - camera.eye).dot(&forward);
+            if depth + body.radius <= 0.0 {
+                continue;
+            }
+            near = near.min(depth - body.radius);
+            far = far.max(depth + body.radius);
+        }
+
+        if far <= MIN_NEAR {
+            // No hay nada delante de la cámara
+            return (0.1, 1000.0);
+        }
+        let far = far * 1.01;
+        (near.max(far / MAX_DEPTH_RATIO).max(MIN_NEAR), far)
+    }
+
+    // El cuerpo más cercano que cruza el rayo que parte de `origin` en la dirección unitaria `direction`
+    pub fn pick(&self, planet: u8, time: f32, origin: Vec3, direction: Vec3) -> Option<PlacedBody> {
+        self.bodies(planet, time)
+            .into_iter()
+            .filter_map(|body| ray_sphere(origin, direction, body.position, body.radius).map(|t| (t, body)))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, body)| body)
+    }
+
+    // Objetos que dibuja `render` para el planeta dado, en el orden en que se dibujan
+    pub fn objects(planet: u8, time: f32) -> Vec<SceneObject> {
+        let days = time * DAYS_PER_SECOND;
+        let mut objects = Vec::new();
+
+        let mut place = |name: &'static str, shader: u8, position: Vec3, scale: f32, rotation: Vec3| {
+            objects.push(SceneObject { name, shader, model_matrix: create_model_matrix(position, scale, rotation) });
+        };
+
+        if planet == 0 {
+            // El Sol queda en el origen
+            place(body::SUN.name, 7, Vec3::new(0.0, 0.0, 0.0), SYSTEM_SUN_SCALE, body::SUN.rotation(days));
+
+            for (shader, scale) in SYSTEM_PLANETS {
+                let position = OrbitalElements::for_shader(shader).unwrap().position(days) * AU_SCALE;
+                let rotation = Body::for_shader(shader).rotation(days);
+                place(Body::for_shader(shader).name, shader, position, scale, rotation);
+
+                if shader == 2 {
+                    // La órbita de la luna está medida en radios del planeta
+                    let moon_position = position + orbit::MOON.position(days) * scale;
+                    place(body::MOON.name, 8, moon_position, scale * 0.5, body::MOON.rotation(days));
+                } else if shader == 4 {
+                    place("Saturn rings", 9, position, scale * 1.5, rotation);
+                }
+            }
+        } else {
+            // El planeta queda en el origen con su rotación axial
+            let rotation = Body::for_shader(planet).rotation(days);
+            place(Body::for_shader(planet).name, planet, Vec3::new(0.0, 0.0, 0.0), 1.0, rotation);
+
+            if planet == 2 {
+                // Luna de Marte, a la mitad de la escala del planeta
+                place(body::MOON.name, 8, orbit::MOON.position(days), 0.5, body::MOON.rotation(days));
+            } else if planet == 4 {
+                // Los anillos, más grandes que el planeta, comparten la inclinación de Saturno
+                place("Saturn rings", 9, Vec3::new(0.0, 0.0, 0.0), 1.5, rotation);
+            }
+        }
+
+        objects
+    }
+
+    // Dibuja un cuadro completo. El resultado depende del planeta, la cámara y el tiempo de simulación,
+    // y de los niveles de detalle en `lod` sólo cuando un objeto está cerca de un umbral. `lod` queda
+    // con los niveles de este cuadro. Devuelve lo que costó cada objeto y cada efecto
+    pub fn render(&self, framebuffer: &mut Framebuffer, camera: &Camera, planet: u8, time: f32, lod: &mut LodState) -> FrameProfile {
+        let mut frame = FrameContext { camera, time, lod, profile: FrameProfile::new() };
+        framebuffer.clear();
+
+        let (near, far) = self.clip_range(camera, planet, time);
+        let view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
+        let projection_matrix = create_projection_matrix(camera, framebuffer.width as f32, framebuffer.height as f32, near, far);
+        let viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+
+        for object in Scene::objects(planet, time) {
+            let uniforms = Uniforms { view_matrix, projection_matrix, viewport_matrix, time, ..object.uniforms() };
+            self.draw(&mut frame, object.name, framebuffer, &uniforms, self.body_mesh(object.shader));
+        }
+
+        if !self.post_effects(planet).is_empty() {
+            apply_sun_glow(framebuffer, &mut frame.profile);
+        }
+
+        frame.profile
+    }
+
+    // Dibuja un objeto con el nivel de detalle que le corresponde por su tamaño en pantalla y anota
+    // en el perfil lo que costó
+    fn draw(&self, frame: &mut FrameContext, name: &'static str, framebuffer: &mut Framebuffer, uniforms: &Uniforms, lod: &LodMesh) {
+        // La matriz de modelo es traslación * escala * rotación
+        let model = &uniforms.model_matrix;
+        let center = Vec3::new(model[(0, 3)], model[(1, 3)], model[(2, 3)]);
+        let scale = Vec3::new(model[(0, 0)], model[(1, 0)], model[(2, 0)]).magnitude();
+        let radius = projected_radius(frame.camera, center, lod.radius() * scale, framebuffer.height as f32);
+
+        let level = lod.select(radius, frame.lod.level(name));
+        frame.lod.levels.insert(name, level);
+
+        let start = Instant::now();
+        let stats = draw_indexed(framebuffer, uniforms, lod.level(level), frame.time);
+        frame.profile.add_object(name, level, start, stats);
+    }
+}
+
+// Distancia a lo largo del rayo hasta la primera intersección con la esfera, si está delante del origen
+fn ray_sphere(origin: Vec3, direction: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let offset = origin - center;
+    let b = offset.dot(&direction);
+    let c = offset.magnitude_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    [-b - root, -b + root].into_iter().find(|&t| t > 0.0)
+}
+
+fn apply_sun_glow(framebuffer: &mut Framebuffer, profile: &mut FrameProfile) {
+    // Aplicar Gaussian Blur al buffer emisivo
+    let kernel_size = 20; // Tamaño del kernel más grande para un desenfoque más suave y amplio
+    let sigma = 2.5; // Sigma para un desenfoque que produce un buen efecto de bloom
+    profile.time_stage("blur", || {
+        gaussian_blur(&mut framebuffer.emissive_buffer, framebuffer.width, framebuffer.height, kernel_size, sigma)
+    });
+
+    // Aplicar Bloom
+    profile.time_stage("bloom", || apply_bloom(&mut framebuffer.buffer, &framebuffer.emissive_buffer));
+}
+
+// Una esfera de radio `radius` con el mesh dado como nivel más detallado y, debajo, icoesferas
+fn sphere_lods(finest: IndexedMesh, radius: f32, levels: &[(u32, f32)]) -> LodMesh {
+    levels.iter().fold(LodMesh::new(finest), |lod, &(subdivisions, below_radius)| {
+        lod.with_level(scaled_mesh(icosphere(subdivisions), radius), below_radius)
+    })
+}
+
+fn scaled_mesh(sphere: Mesh, radius: f32) -> IndexedMesh {
+    let mut mesh = sphere.get_indexed_mesh();
+    for vertex in &mut mesh.vertices {
+        vertex.position *= radius;
+    }
+    mesh
+}
+
+// Renderiza un cuadro sin ventana, para pruebas y exportación. Cada cuadro elige sus niveles de
+// detalle sin histéresis, así que sólo depende de la escena, la cámara y el tiempo
+pub fn render_headless(scene: &Scene, camera: &Camera, planet: u8, time: f32, width: usize, height: usize) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(width, height);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    scene.render(&mut framebuffer, camera, planet, time, &mut LodState::new());
+    framebuffer
+}
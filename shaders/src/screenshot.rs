@@ -0,0 +1,121 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use nalgebra_glm::Vec3;
+use crate::body::Body;
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::image_io::{save_png_with_metadata, save_grayscale_png};
+use crate::noise::{create_noise, create_cloud_noise, create_jupiter_band_noise, samples_noise};
+use crate::scene::Scene;
+
+// Guarda el cuadro actual en `directory` con nombre según la fecha (UTC). Con `include_buffers`
+// también guarda la profundidad y el buffer emisivo. Devuelve las rutas escritas
+pub fn capture(
+    directory: &Path,
+    framebuffer: &Framebuffer,
+    camera: &Camera,
+    planet: u8,
+    time: f32,
+    include_buffers: bool,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(directory)?;
+
+    let name = planet_name(planet).to_lowercase().replace(' ', "-");
+    let stem = format!("{}-{}", name, timestamp(SystemTime::now()));
+    let metadata = shot_metadata(camera, planet, time);
+    let (width, height) = (framebuffer.width, framebuffer.height);
+
+    let mut paths = vec![directory.join(format!("{}.png", stem))];
+    save_png_with_metadata(&paths[0], width, height, &framebuffer.buffer, &metadata)?;
+
+    if include_buffers {
+        // Profundidad normalizada entre el punto más cercano y el más lejano; el fondo queda en negro
+        let finite = framebuffer.zbuffer.iter().filter(|depth| depth.is_finite());
+        let near = finite.clone().fold(f32::INFINITY, |a, &b| a.min(b));
+        let far = finite.fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+        let range = (far - near).max(f32::EPSILON);
+        let depth: Vec<f32> = framebuffer.zbuffer.iter()
+            .map(|&z| if z.is_finite() { 1.0 - (z - near) / range } else { 0.0 })
+            .collect();
+
+        let mut depth_metadata = metadata.clone();
+        depth_metadata.push(("DepthRange", format!("{} {}", near, far)));
+        let depth_path = directory.join(format!("{}-depth.png", stem));
+        save_grayscale_png(&depth_path, width, height, &depth, &depth_metadata)?;
+        paths.push(depth_path);
+
+        let emission: Vec<f32> = framebuffer.emissive_buffer.iter().map(|&e| e as f32 / 255.0).collect();
+        let emissive_path = directory.join(format!("{}-emissive.png", stem));
+        save_grayscale_png(&emissive_path, width, height, &emission, &metadata)?;
+        paths.push(emissive_path);
+    }
+
+    Ok(paths)
+}
+
+fn planet_name(planet: u8) -> &'static str {
+    if planet == 0 { "Solar System" } else { Body::for_shader(planet).name }
+}
+
+fn shot_metadata(camera: &Camera, planet: u8, time: f32) -> Vec<(&'static str, String)> {
+    let vector = |v: Vec3| format!("{} {} {}", v.x, v.y, v.z);
+
+    // Sólo las semillas de los shaders que se dibujan en el cuadro y que leen ruido. Las nubes son
+    // de la Tierra y las bandas de Júpiter
+    let shaders: Vec<u8> = Scene::objects(planet, time).iter().map(|object| object.shader).collect();
+    let mut seeds: Vec<String> = shaders.iter()
+        .filter(|&&shader| samples_noise(shader))
+        .map(|&shader| format!("{}={}", Body::for_shader(shader).name, create_noise(shader).seed))
+        .collect();
+    if shaders.contains(&1) {
+        seeds.push(format!("cloud={}", create_cloud_noise().seed));
+    }
+    if shaders.contains(&5) {
+        seeds.push(format!("band={}", create_jupiter_band_noise().seed));
+    }
+
+    vec![
+        ("Title", planet_name(planet).to_string()),
+        ("Planet", planet.to_string()),
+        ("CameraEye", vector(camera.eye)),
+        ("CameraCenter", vector(camera.center)),
+        ("CameraUp", vector(camera.up)),
+        ("CameraFov", camera.fov.to_string()),
+        ("CameraProjection", format!("{:?}", camera.projection)),
+        ("LensShift", format!("{} {}", camera.lens_shift.x, camera.lens_shift.y)),
+        ("SimulationTime", time.to_string()),
+        ("NoiseSeeds", seeds.join(" ")),
+        ("Software", format!("shaders {}", env!("CARGO_PKG_VERSION"))),
+    ]
+}
+
+// AAAAMMDD-HHMMSS-mmm en UTC
+fn timestamp(now: SystemTime) -> String {
+    let elapsed = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = elapsed.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let seconds_of_day = seconds % 86400;
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year, month, day,
+        seconds_of_day / 3600, (seconds_of_day / 60) % 60, seconds_of_day % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+// Días desde 1970-01-01 a fecha del calendario gregoriano (algoritmo de Howard Hinnant)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -1,11 +1,22 @@
-use nalgebra_glm::{dot, mat4_to_mat3, normalize, Mat3, Vec2, Vec3, Vec4};
+use nalgebra_glm::{mat4_to_mat3, Mat3, Vec2, Vec3, Vec4};
 use crate::vertex::Vertex;
 use crate::Uniforms;
 use crate::fragment::Fragment;
 use crate::color::Color;
+use crate::noise::{sample_sphere, sample_sphere_at};
 
 
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+  // El relieve mueve el vértice e inclina su normal antes de proyectar nada
+  let displaced;
+  let vertex = match &uniforms.relief {
+    Some(relief) => {
+      displaced = relief.displace(vertex);
+      &displaced
+    }
+    None => vertex,
+  };
+
   // Transform position
   let position = Vec4::new(
     vertex.position.x,
@@ -13,7 +24,8 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     vertex.position.z,
     1.0
   );
-  let transformed = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix * position;
+  let world_position = uniforms.model_matrix * position;
+  let transformed = uniforms.projection_matrix * uniforms.view_matrix * world_position;
 
   // Perform perspective division
   let w = transformed.w;
@@ -33,23 +45,33 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
 
   let transformed_normal = normal_matrix * vertex.normal;
 
+  // Las tangentes están sobre la superficie, así que siguen a la propia matriz de modelo
+  let transformed_tangent = model_mat3 * vertex.tangent;
+  let transformed_bitangent = model_mat3 * vertex.bitangent;
+
   // Create a new Vertex with transformed attributes
   Vertex {
     position: vertex.position,
     normal: vertex.normal,
+    tangent: vertex.tangent,
+    bitangent: vertex.bitangent,
     tex_coords: vertex.tex_coords,
     color: vertex.color,
     transformed_position: Vec3::new(screen_position.x, screen_position.y, screen_position.z),
+    clip_position: transformed,
     transformed_normal,
+    transformed_tangent,
+    transformed_bitangent,
+    world_position: Vec3::new(world_position.x, world_position.y, world_position.z),
   }
 }
 
-pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color, u32) {
+pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, time: f32) -> (Color, u32) {
   match uniforms.current_shader {
       1 => earth_shader(fragment, uniforms, time),
       2 => mars_planet_shader(fragment, uniforms),
       3 => mercury_shader(fragment, uniforms),
-      4 => saturn_shader(fragment),
+      4 => saturn_shader(fragment, uniforms),
       5 => jupiter_shader(fragment, uniforms),
       6 => urano_shader(fragment, uniforms, time),
       7 => sun_shader(),
@@ -59,17 +81,42 @@ pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (
   }
 }
 
-fn earth_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color, u32) {
+// Término de Lambert que comparten los shaders iluminados. Cada shader le suma su propia luz ambiental
+fn diffuse(fragment: &Fragment, uniforms: &Uniforms, light_direction: Vec3) -> f32 {
+  shading_normal(fragment, uniforms).dot(&light_direction).max(0.0)
+}
+
+// Dirección desde el fragmento hacia una luz puntual
+fn point_light(fragment: &Fragment, light_position: Vec3) -> Vec3 {
+  (light_position - fragment.world_position).normalize()
+}
+
+// Normal con que se ilumina. En los cuerpos con relieve lleva bump mapping: la normal interpolada
+// se inclina según la pendiente del detalle que cae entre vértices, así los cráteres y cordilleras
+// pequeños siguen mirando hacia la luz o lejos de ella en un mesh grueso
+pub fn shading_normal(fragment: &Fragment, uniforms: &Uniforms) -> Vec3 {
+  let normal = fragment.normal.normalize();
+  let Some(relief) = &uniforms.relief else {
+    return normal;
+  };
+
+  // La pendiente se mide en espacio de modelo; la matriz de modelo sólo agrega rotación y una escala uniforme
+  let model = mat4_to_mat3(&uniforms.model_matrix);
+  let scale = (model * Vec3::x()).magnitude();
+  let slope = model * relief.detail_slope(&fragment.vertex_position) / scale;
+  let slope = slope - normal * normal.dot(&slope);
+  (normal - slope).normalize()
+}
+
+fn earth_shader(fragment: &Fragment, uniforms: &Uniforms, time: f32) -> (Color, u32) {
   let zoom = 100.0;  // to move our values 
   let ox = 100.0; // offset x in the noise map
   let oy = 100.0;
-  let x = fragment.vertex_position.x;
-  let y = fragment.vertex_position.y;
-  let t = time as f32 * 0.1;
+  let t = time * 100.0;
 
-  let base_noise_value = uniforms.noise.get_noise_2d(x, y);
-  let cloud_noise_value = uniforms.cloud_noise.get_noise_2d(
-      x * zoom + ox +t, y * zoom + oy
+  let base_noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 1.0);
+  let cloud_noise_value = sample_sphere_at(
+      &uniforms.cloud_noise, &fragment.vertex_position, zoom, Vec3::new(ox + t, oy, 0.0)
   );
 
   // Colores base para el agua y la tierra
@@ -90,14 +137,12 @@ fn earth_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color,
 
   // Iluminación más dramática
   let light_position = Vec3::new(1.0, 1.0, 3.0); // Posición de la luz ajustada para mayor contraste
-  let light_dir = normalize(&(light_position - fragment.vertex_position)); // Dirección de la luz ajustada
-  let normal = normalize(&fragment.normal); // Normalizar la normal
-  let diffuse = dot(&normal, &light_dir).max(0.0); // Cálculo de la componente difusa
+  let diffuse = diffuse(fragment, uniforms, point_light(fragment, light_position)); // Cálculo de la componente difusa
 
   let lit_color = base_color * (0.1 + 0.9 * diffuse); 
 
   let cloud_threshold = 0.1; // Umbral para la aparición de nubes
-  let cloud_opacity = 0.3 + 0.2 * ((time as f32 / 1000.0) * 0.3).sin().abs(); 
+  let cloud_opacity = 0.3 + 0.2 * (time * 0.3).sin().abs(); 
   if cloud_noise_value > cloud_threshold {
       let cloud_intensity = ((cloud_noise_value - cloud_threshold) / (1.0 - cloud_threshold)).clamp(0.0, 1.0);
       (lit_color.blend_add(&(cloud_color * (cloud_intensity * cloud_opacity))), 0)
@@ -107,7 +152,7 @@ fn earth_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color,
 }
 
 fn mars_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
-  let noise_value = uniforms.noise.get_noise_2d(fragment.vertex_position.x, fragment.vertex_position.y);
+  let noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 1.0);
   
   let dark_red = Color::from_float(0.4, 0.1, 0.1); // Color oscuro para áreas en sombra
   let bright_orange = Color::from_float(0.8, 0.4, 0.1); // Color brillante para áreas iluminadas
@@ -123,13 +168,10 @@ fn mars_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32)
 
   // Definir la posición y dirección de la luz
   let light_pos = Vec3::new(0.0, 8.0, 9.0);  // Posición de la fuente de luz
-  let light_dir = (light_pos - fragment.vertex_position).normalize(); // Dirección de la luz desde la posición del fragmento
-
-  // Normalizar la normal del fragmento
-  let normal = fragment.normal.normalize();
+  let light_dir = point_light(fragment, light_pos); // Dirección de la luz desde la posición del fragmento
 
-  // Calcular la intensidad de la luz difusa
-  let diffuse_intensity = normal.dot(&light_dir).max(0.0);
+  // Calcular la intensidad de la luz difusa, con el relieve de la superficie
+  let diffuse_intensity = diffuse(fragment, uniforms, light_dir);
 
   // Modificar el color final basado en la intensidad de la luz
   let lit_color = base_color * diffuse_intensity;  // Modula el color por la intensidad de la luz
@@ -150,7 +192,7 @@ pub fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   let detail_color = Color::from_float(0.3, 0.3, 0.3); // Gris más oscuro para detalles
 
   // Genera variaciones en la superficie
-  let noise_value = uniforms.noise.get_noise_2d(fragment.vertex_position.x, fragment.vertex_position.y);
+  let noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 1.0);
 
   // Normaliza el valor del ruido a [0, 1]
   let normalized_noise = (noise_value + 1.0) * 0.5; // Ajusta según el rango real de tu generador de ruido
@@ -158,9 +200,7 @@ pub fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
 
   // Iluminación simple
   let light_position = Vec3::new(10.0, 10.0, 10.0);
-  let light_direction = (light_position - fragment.vertex_position).normalize();
-  let normal = fragment.normal.normalize();
-  let diffuse = normal.dot(&light_direction).max(0.0);
+  let diffuse = diffuse(fragment, uniforms, point_light(fragment, light_position));
 
   // Combinar color de superficie con iluminación
   (surface_variation * (0.3 + 0.7 * diffuse), 0)
@@ -175,9 +215,9 @@ pub fn mercury_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32)
   let yellow_light = Color::from_float(0.8, 0.7, 0.4);
 
   // Genera ruido para variaciones de color
-  let noise_value1 = uniforms.noise.get_noise_2d(fragment.vertex_position.x, fragment.vertex_position.y);
-  let noise_value2 = uniforms.noise.get_noise_2d(fragment.vertex_position.x * 2.0, fragment.vertex_position.y * 2.0); // Ajustar frecuencia
-  let noise_value3 = uniforms.noise.get_noise_2d(fragment.vertex_position.x * 0.5, fragment.vertex_position.y * 0.5); // Baja frecuencia
+  let noise_value1 = sample_sphere(&uniforms.noise, &fragment.vertex_position, 1.0);
+  let noise_value2 = sample_sphere(&uniforms.noise, &fragment.vertex_position, 2.0); // Ajustar frecuencia
+  let noise_value3 = sample_sphere(&uniforms.noise, &fragment.vertex_position, 0.5); // Baja frecuencia
 
   // Normaliza los valores de ruido
   let lerp_factor1 = (noise_value1 + 1.0) * 0.5; // Normalizar a [0, 1]
@@ -192,9 +232,7 @@ pub fn mercury_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32)
 
   // Iluminación para dar más realismo
   let light_position = Vec3::new(0.0, 8.0, 9.0);
-  let light_direction = (light_position - fragment.vertex_position).normalize();
-  let normal = fragment.normal.normalize();
-  let diffuse = normal.dot(&light_direction).max(0.0);
+  let diffuse = diffuse(fragment, uniforms, point_light(fragment, light_position));
 
   // Combinación de la iluminación con el color
   let ambient_intensity = 0.15;
@@ -226,10 +264,7 @@ fn jupiter_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   let band_frequency = 10.0;
 
   // Agregar ruido al patrón de bandas
-  let band_noise = uniforms.band_noise.get_noise_2d(
-      fragment.vertex_position.x * 2.0,
-      fragment.vertex_position.y * 2.0,
-  );
+  let band_noise = sample_sphere(&uniforms.band_noise, &fragment.vertex_position, 2.0);
   let band_noise_intensity = 0.2;
   let distorted_latitude = latitude + band_noise * band_noise_intensity;
   let band_pattern = (distorted_latitude * band_frequency).sin();
@@ -250,21 +285,13 @@ fn jupiter_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   let base_color = color1.lerp(&color2, t);
 
   // Capa 2: Turbulencia con ruido
-  let noise_value = uniforms.noise.get_noise_3d(
-      fragment.vertex_position.x * 4.0,
-      fragment.vertex_position.y * 4.0,
-      fragment.vertex_position.z * 4.0,
-  );
+  let noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 4.0);
 
   let turbulence_intensity = 0.3;
   let turbulence_color = base_color.lerp(&Color::from_hex(0xffffff), noise_value * turbulence_intensity);
 
   // Capa adicional: Variación de color con ruido
-  let color_noise_value = uniforms.noise.get_noise_3d(
-      fragment.vertex_position.x * 3.0,
-      fragment.vertex_position.y * 3.0,
-      fragment.vertex_position.z * 3.0,
-  );
+  let color_noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 3.0);
 
   let terracotta_variation_color = Color::from_hex(0x955d36); // Color terracota para variaciones
   let gray_variation_color = Color::from_hex(0xc7c7cf); // Gris claro para variaciones
@@ -281,10 +308,9 @@ fn jupiter_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   let red_spot_center = Vec2::new(0.65, 0.5);
   let distance_to_spot = (uv - red_spot_center).norm();
 
-  let red_spot_noise_value = uniforms.noise.get_noise_2d(
-      uv.x * 20.0,
-      uv.y * 20.0,
-  );
+  // El ruido de la mancha tiñe todo el planeta, así que se muestrea sobre la esfera y no en uv,
+  // donde se cortaría en la costura y se apretaría en los polos
+  let red_spot_noise_value = sample_sphere(&uniforms.noise, &fragment.vertex_position, 5.0);
   let red_spot_noise_intensity = red_spot_noise_value * 0.3;
 
   let red_spot_radius = 0.1;
@@ -302,9 +328,7 @@ fn jupiter_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
 
   // Iluminación
   let light_position = Vec3::new(0.0, 8.0, 9.0);
-  let light_direction = (light_position - fragment.vertex_position).normalize();
-  let normal = fragment.normal.normalize();
-  let diffuse = normal.dot(&light_direction).max(0.0);
+  let diffuse = diffuse(fragment, uniforms, point_light(fragment, light_position));
 
   // Combinación de la iluminación con el color
   let ambient_intensity = 0.15;
@@ -317,14 +341,11 @@ fn jupiter_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   (color_with_lighting, 0)
 }
 
-fn urano_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color, u32) {
-  let x = fragment.vertex_position.x;
-  let y = fragment.vertex_position.y;
-  let z = fragment.vertex_position.z;
-  let t = time as f32 * 0.001; // Escala de tiempo para el movimiento
+fn urano_shader(fragment: &Fragment, uniforms: &Uniforms, time: f32) -> (Color, u32) {
+  let t = time; // Escala de tiempo para el movimiento
 
   // Coordenadas de ruido para simular movimiento atmosférico
-  let noise_value = uniforms.noise.get_noise_3d(x, y + t, z);
+  let noise_value = sample_sphere_at(&uniforms.noise, &fragment.vertex_position, 1.0, Vec3::new(0.0, t, 0.0));
 
   // Color base de Urano
   let base_color = Color::from_float(0.2, 0.5, 0.9); // Un azul característico de Urano
@@ -335,15 +356,14 @@ fn urano_shader(fragment: &Fragment, uniforms: &Uniforms, time: u32) -> (Color,
 
   // Iluminación direccional para resaltar la textura
   let light_dir = Vec3::new(1.0, 1.0, 1.0).normalize(); // Dirección de luz arbitraria
-  let normal = fragment.normal.normalize(); // Normalizar la normal del fragmento
-  let diffuse = normal.dot(&light_dir).max(0.0); // Cálculo difuso
+  let diffuse = diffuse(fragment, uniforms, light_dir); // Cálculo difuso
   let ambient = 0.3; // Intensidad ambiental
   let lit_color = varied_color * (ambient + (1.0 - ambient) * diffuse); // Combinación de iluminación
 
   (lit_color, 0)
 }
 
-fn saturn_shader(fragment: &Fragment) -> (Color, u32) {
+fn saturn_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, u32) {
   // Normalizar la latitud de -1 a 1 a un rango de 0 a 1
   let latitude = (fragment.vertex_position.y + 1.0) * 0.5;
 
@@ -372,9 +392,7 @@ fn saturn_shader(fragment: &Fragment) -> (Color, u32) {
 
   // Aplicar iluminación básica
   let light_position = Vec3::new(1.0, 1.0, 10.0);
-  let light_direction = (light_position - fragment.vertex_position).normalize();
-  let normal = fragment.normal.normalize();
-  let diffuse = normal.dot(&light_direction).max(0.0);
+  let diffuse = diffuse(fragment, uniforms, point_light(fragment, light_position));
 
   let ambient_intensity = 0.1;  // Ajustar según la escena
   let ambient_color = color * ambient_intensity;
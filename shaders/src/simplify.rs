@@ -0,0 +1,360 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use nalgebra_glm::Vec3;
+use crate::mesh::position_key;
+use crate::obj::{Mesh, Obj};
+
+// Simplificación por métricas de error cuadrático (Garland y Heckbert). Cada posición acumula los
+// planos de sus caras y se colapsan primero las aristas que menos alejan la superficie de esos
+// planos. Los colapsos van de un vértice a otro ya existente, así que los que quedan conservan sus
+// normales y coordenadas de textura tal cual
+
+// Peso de los planos que sujetan los bordes abiertos y las costuras, respecto a los de las caras
+const CONSTRAINT_WEIGHT: f64 = 1000.0;
+// Coseno mínimo entre la normal de una cara antes y después de un colapso
+const MIN_FACE_COS: f32 = 0.2;
+
+impl Obj {
+    // Copia del modelo con a lo sumo `target_triangles` triángulos, repartidos entre los meshes según
+    // cuántos tenía cada uno. Los materiales no cambian
+    pub fn simplify(&self, target_triangles: usize) -> Obj {
+        let total: usize = self.meshes.iter().map(Mesh::triangle_count).sum();
+        let target = target_triangles.min(total);
+        let mut shares: Vec<usize> = self.meshes.iter()
+            .map(|mesh| (target * mesh.triangle_count()).checked_div(total).unwrap_or(0))
+            .collect();
+        // Lo que se pierde al redondear hacia abajo va al mesh más grande, así la suma es el objetivo
+        if let Some(largest) = (0..self.meshes.len()).max_by_key(|&index| self.meshes[index].triangle_count()) {
+            shares[largest] += target - shares.iter().sum::<usize>();
+        }
+        let meshes = self.meshes.iter().zip(shares).map(|(mesh, share)| mesh.simplify(share)).collect();
+        Obj { meshes, materials: self.materials.clone(), libraries: self.libraries.clone() }
+    }
+}
+
+impl Mesh {
+    // Copia con `target_triangles` triángulos o los menos que se pueda sin romper la superficie.
+    // Las costuras de textura, los pliegues de normales y los bordes abiertos sólo se acortan a lo
+    // largo de sí mismos, así que las texturas no se deforman y los bordes no se encogen
+    pub fn simplify(&self, target_triangles: usize) -> Mesh {
+        let mut simplifier = Simplifier::new(self);
+        simplifier.run(target_triangles);
+        let mut mesh = simplifier.into_mesh(self);
+        mesh.material = self.material.clone();
+        mesh
+    }
+}
+
+// Una cara que toca la arista y sus dos vértices en ella, en el orden de las posiciones
+type EdgeSide = (usize, [usize; 2]);
+
+// Forma cuadrática simétrica de 4x4 que mide la suma de las distancias al cuadrado a unos planos,
+// guardada como su triángulo superior
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    // Plano con normal unitaria `normal` que pasa por `point`
+    fn plane(normal: Vec3, point: Vec3, weight: f64) -> Self {
+        let (a, b, c) = (normal.x as f64, normal.y as f64, normal.z as f64);
+        let d = -(a * point.x as f64 + b * point.y as f64 + c * point.z as f64);
+        Quadric([a * a, a * b, a * c, a * d, b * b, b * c, b * d, c * c, c * d, d * d].map(|q| q * weight))
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (q, o) in self.0.iter_mut().zip(other.0) {
+            *q += o;
+        }
+    }
+
+    fn error(&self, p: Vec3) -> f64 {
+        let (x, y, z) = (p.x as f64, p.y as f64, p.z as f64);
+        let q = &self.0;
+        q[0] * x * x + 2.0 * q[1] * x * y + 2.0 * q[2] * x * z + 2.0 * q[3] * x
+            + q[4] * y * y + 2.0 * q[5] * y * z + 2.0 * q[6] * y
+            + q[7] * z * z + 2.0 * q[8] * z
+            + q[9]
+    }
+}
+
+// Colapso de la posición `from` sobre `to`. Las versiones de las dos posiciones al momento de
+// calcular el costo permiten descartar los candidatos que quedaron viejos
+struct Candidate {
+    cost: f64,
+    from: usize,
+    to: usize,
+    versions: (u32, u32),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    // Al revés, para que el montículo dé primero el más barato
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+// Los vértices del mesh son esquinas: una misma posición se repite en las costuras con otra
+// coordenada de textura o normal. Las aristas se colapsan por posición y cada esquina de la
+// posición que desaparece pasa a una esquina de la que queda
+struct Simplifier {
+    // Posición de cada vértice, con los vértices de un mismo punto juntos, y dónde está cada posición
+    position_of: Vec<usize>,
+    positions: Vec<Vec3>,
+    faces: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    alive_count: usize,
+    // Caras de cada posición; puede haber caras muertas o que ya no la usan
+    incident: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    versions: Vec<u32>,
+    heap: BinaryHeap<Candidate>,
+}
+
+impl Simplifier {
+    fn new(mesh: &Mesh) -> Self {
+        let mut ids: HashMap<[u32; 3], usize> = HashMap::new();
+        let mut positions = Vec::new();
+        let position_of: Vec<usize> = mesh.vertices.iter().map(|&vertex| {
+            *ids.entry(position_key(vertex)).or_insert_with(|| {
+                positions.push(vertex);
+                positions.len() - 1
+            })
+        }).collect();
+
+        // Los triángulos que ya tienen dos esquinas en el mismo punto no aportan nada
+        let faces: Vec<[usize; 3]> = mesh.indices.chunks_exact(3)
+            .map(|t| [t[0] as usize, t[1] as usize, t[2] as usize])
+            .filter(|t| {
+                let [a, b, c] = t.map(|vertex| position_of[vertex]);
+                a != b && b != c && c != a
+            })
+            .collect();
+
+        let mut simplifier = Simplifier {
+            alive: vec![true; faces.len()],
+            alive_count: faces.len(),
+            incident: vec![Vec::new(); positions.len()],
+            quadrics: vec![Quadric::default(); positions.len()],
+            versions: vec![0; positions.len()],
+            heap: BinaryHeap::new(),
+            position_of,
+            positions,
+            faces,
+        };
+        simplifier.build_quadrics();
+        for position in 0..simplifier.positions.len() {
+            simplifier.push_edges(position);
+        }
+        simplifier
+    }
+
+    fn build_quadrics(&mut self) {
+        // Aristas por par de posiciones, con las esquinas que usa cada cara
+        let mut edges: HashMap<(usize, usize), Vec<EdgeSide>> = HashMap::new();
+        for (face, corners) in self.faces.iter().enumerate() {
+            let [a, b, c] = corners.map(|vertex| self.positions[self.position_of[vertex]]);
+            let cross = (b - a).cross(&(c - a));
+            let area = cross.magnitude() as f64 / 2.0;
+            if area > 0.0 {
+                let plane = Quadric::plane(cross.normalize(), a, area);
+                for &vertex in corners {
+                    self.quadrics[self.position_of[vertex]].add(&plane);
+                }
+            }
+            for k in 0..3 {
+                let (first, second) = (corners[k], corners[(k + 1) % 3]);
+                let (p, q) = (self.position_of[first], self.position_of[second]);
+                self.incident[p].push(face);
+                let entry = if p < q { ((p, q), [first, second]) } else { ((q, p), [second, first]) };
+                edges.entry(entry.0).or_default().push((face, entry.1));
+            }
+        }
+
+        // Bordes abiertos y costuras: un plano perpendicular a la cara que contiene la arista
+        for ((p, q), sides) in edges {
+            let is_seam = sides.len() == 2 && sides[0].1 != sides[1].1;
+            if sides.len() != 1 && !is_seam {
+                continue;
+            }
+            let (a, b) = (self.positions[p], self.positions[q]);
+            for (face, _) in sides {
+                let normal = self.face_normal(face, None);
+                let across = (b - a).cross(&normal);
+                if across.magnitude_squared() == 0.0 {
+                    continue;
+                }
+                let constraint = Quadric::plane(across.normalize(), a, CONSTRAINT_WEIGHT * (b - a).magnitude_squared() as f64);
+                self.quadrics[p].add(&constraint);
+                self.quadrics[q].add(&constraint);
+            }
+        }
+    }
+
+    fn run(&mut self, target_triangles: usize) {
+        while self.alive_count > target_triangles {
+            let Some(candidate) = self.heap.pop() else {
+                break;
+            };
+            if (self.versions[candidate.from], self.versions[candidate.to]) != candidate.versions {
+                continue;
+            }
+            if let Some(corners) = self.collapse_map(candidate.from, candidate.to) {
+                self.collapse(candidate.from, candidate.to, &corners);
+            }
+        }
+    }
+
+    // Caras vivas que todavía usan la posición
+    fn faces_of(&self, position: usize) -> impl Iterator<Item = usize> + '_ {
+        self.incident[position].iter().copied()
+            .filter(move |&face| self.alive[face] && self.corner_at(face, position).is_some())
+    }
+
+    // Vértice de la cara que está en la posición
+    fn corner_at(&self, face: usize, position: usize) -> Option<usize> {
+        self.faces[face].iter().copied().find(|&vertex| self.position_of[vertex] == position)
+    }
+
+    fn neighbors(&self, position: usize) -> HashSet<usize> {
+        self.faces_of(position)
+            .flat_map(|face| self.faces[face].map(|vertex| self.position_of[vertex]))
+            .filter(|&other| other != position)
+            .collect()
+    }
+
+    // Normal de la cara, con la posición `moved.0` llevada a `moved.1` si se indica
+    fn face_normal(&self, face: usize, moved: Option<(usize, Vec3)>) -> Vec3 {
+        let [a, b, c] = self.faces[face].map(|vertex| {
+            let position = self.position_of[vertex];
+            match moved {
+                Some((from, to)) if from == position => to,
+                _ => self.positions[position],
+            }
+        });
+        let normal = (b - a).cross(&(c - a));
+        if normal.magnitude_squared() > 0.0 { normal.normalize() } else { normal }
+    }
+
+    fn push_edges(&mut self, position: usize) {
+        for neighbor in self.neighbors(position) {
+            for (from, to) in [(position, neighbor), (neighbor, position)] {
+                let mut quadric = self.quadrics[from];
+                quadric.add(&self.quadrics[to]);
+                self.heap.push(Candidate {
+                    cost: quadric.error(self.positions[to]),
+                    from,
+                    to,
+                    versions: (self.versions[from], self.versions[to]),
+                });
+            }
+        }
+    }
+
+    // A qué vértice de `to` pasa cada vértice de `from`, o nada si el colapso no se puede hacer
+    fn collapse_map(&self, from: usize, to: usize) -> Option<HashMap<usize, usize>> {
+        let mut corners: HashMap<usize, usize> = HashMap::new();
+        let mut shared = 0;
+        for face in self.faces_of(from) {
+            if let Some(target) = self.corner_at(face, to) {
+                let source = self.corner_at(face, from)?;
+                if *corners.entry(source).or_insert(target) != target {
+                    return None;
+                }
+                shared += 1;
+            }
+        }
+        if shared == 0 {
+            return None;
+        }
+
+        // Una esquina de `from` que no toca la arista está del otro lado de una costura o un
+        // borde, y moverla cruzaría la textura o despegaría el borde
+        let common = self.neighbors(from).intersection(&self.neighbors(to)).count();
+        if common != shared {
+            return None;
+        }
+        for face in self.faces_of(from) {
+            if self.corner_at(face, to).is_some() {
+                continue;
+            }
+            if !corners.contains_key(&self.corner_at(face, from)?) {
+                return None;
+            }
+            let before = self.face_normal(face, None);
+            let after = self.face_normal(face, Some((from, self.positions[to])));
+            if after.magnitude_squared() == 0.0 || before.dot(&after) < MIN_FACE_COS {
+                return None;
+            }
+        }
+        Some(corners)
+    }
+
+    fn collapse(&mut self, from: usize, to: usize, corners: &HashMap<usize, usize>) {
+        for face in std::mem::take(&mut self.incident[from]) {
+            if !self.alive[face] || self.corner_at(face, from).is_none() {
+                continue;
+            }
+            if self.corner_at(face, to).is_some() {
+                self.alive[face] = false;
+                self.alive_count -= 1;
+                continue;
+            }
+            for vertex in &mut self.faces[face] {
+                if let Some(&target) = corners.get(vertex) {
+                    *vertex = target;
+                }
+            }
+            self.incident[to].push(face);
+        }
+
+        let quadric = self.quadrics[from];
+        self.quadrics[to].add(&quadric);
+
+        // Cambian el costo de las aristas de `to` y la validez de las de sus vecinos
+        let neighbors = self.neighbors(to);
+        self.versions[from] += 1;
+        self.versions[to] += 1;
+        for &neighbor in &neighbors {
+            self.versions[neighbor] += 1;
+        }
+        self.push_edges(to);
+        for neighbor in neighbors {
+            self.push_edges(neighbor);
+        }
+    }
+
+    // Mesh con las caras vivas y sólo los vértices que usan
+    fn into_mesh(self, original: &Mesh) -> Mesh {
+        let mut remap: HashMap<usize, u32> = HashMap::new();
+        let (mut vertices, mut normals, mut texcoords, mut indices) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (face, corners) in self.faces.iter().enumerate() {
+            if !self.alive[face] {
+                continue;
+            }
+            for &vertex in corners {
+                let index = *remap.entry(vertex).or_insert_with(|| {
+                    vertices.push(original.vertices[vertex]);
+                    normals.push(original.normals[vertex]);
+                    texcoords.push(original.texcoords[vertex]);
+                    (vertices.len() - 1) as u32
+                });
+                indices.push(index);
+            }
+        }
+        Mesh::from_buffers(&original.name, vertices, normals, texcoords, indices)
+    }
+}
@@ -0,0 +1,150 @@
+use nalgebra_glm::{Vec3, Quat, Mat3, mat3_to_quat, quat_slerp, quat_rotate_vec3};
+use crate::camera::Camera;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+  Linear,
+  EaseInOutQuad,
+  EaseInOutCubic,
+  EaseOutExpo,
+  // Smootherstep: zero velocity and acceleration at both ends
+  Smoother,
+}
+
+impl Easing {
+  pub const ALL: [Easing; 5] = [Easing::Linear, Easing::EaseInOutQuad, Easing::EaseInOutCubic, Easing::EaseOutExpo, Easing::Smoother];
+
+  // Maps linear progress in [0, 1] to eased progress in [0, 1]
+  pub fn apply(self, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match self {
+      Easing::Linear => t,
+      Easing::EaseInOutQuad => {
+        if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+      }
+      Easing::EaseInOutCubic => {
+        if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 }
+      }
+      Easing::EaseOutExpo => {
+        if t >= 1.0 { 1.0 } else { 1.0 - 2.0f32.powf(-10.0 * t) }
+      }
+      Easing::Smoother => t * t * t * (t * (t * 6.0 - 15.0) + 10.0),
+    }
+  }
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Easing::Linear => "linear",
+      Easing::EaseInOutQuad => "quad",
+      Easing::EaseInOutCubic => "cubic",
+      Easing::EaseOutExpo => "expo",
+      Easing::Smoother => "smoother",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Easing> {
+    Easing::ALL.into_iter().find(|easing| easing.name() == name)
+  }
+}
+
+// Everything a transition interpolates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+  pub eye: Vec3,
+  pub center: Vec3,
+  pub up: Vec3,
+  pub fov: f32,
+}
+
+impl CameraPose {
+  pub fn of(camera: &Camera) -> Self {
+    CameraPose { eye: camera.eye, center: camera.center, up: camera.up, fov: camera.fov }
+  }
+
+  pub fn apply(&self, camera: &mut Camera) {
+    camera.eye = self.eye;
+    camera.center = self.center;
+    camera.up = self.up;
+    camera.fov = self.fov;
+    camera.has_changed = true;
+  }
+
+  // Pose looking along the same direction as `camera` with a sphere of `radius` at `target` filling
+  // `fill` of the smaller side of a viewport with the given aspect ratio (width / height)
+  pub fn framing(camera: &Camera, target: Vec3, radius: f32, aspect: f32, fill: f32) -> Self {
+    let half_fov = camera.fov / 2.0;
+    let half_horizontal = (half_fov.tan() * aspect).atan();
+    let half_angle = half_fov.min(half_horizontal);
+
+    // The sphere's silhouette subtends asin(radius / distance) from its center
+    let apparent = (half_angle.tan() * fill).atan();
+    let distance = radius / apparent.sin();
+
+    CameraPose {
+      eye: target - camera.forward() * distance,
+      center: target,
+      up: camera.up,
+      fov: camera.fov,
+    }
+  }
+
+  // Orientation as a quaternion taking camera space (-Z forward, +Y up) to world space
+  fn orientation(&self) -> Quat {
+    let forward = (self.center - self.eye).normalize();
+    let right = forward.cross(&self.up).normalize();
+    let up = right.cross(&forward);
+    mat3_to_quat(&Mat3::from_columns(&[right, up, -forward]))
+  }
+}
+
+// Flies the camera from one pose to another. The center moves in a straight line, the view direction
+// and up vector turn along the shortest arc and the distance changes geometrically, so moving between
+// bodies of very different sizes does not rush through the close-up part
+pub struct CameraTransition {
+  from: CameraPose,
+  to: CameraPose,
+  duration: f32,
+  elapsed: f32,
+  easing: Easing,
+}
+
+impl CameraTransition {
+  pub fn new(from: CameraPose, to: CameraPose, duration: f32, easing: Easing) -> Self {
+    CameraTransition { from, to, duration: duration.max(0.0), elapsed: 0.0, easing }
+  }
+
+  pub fn is_finished(&self) -> bool {
+    self.elapsed >= self.duration
+  }
+
+  pub fn pose_at(&self, progress: f32) -> CameraPose {
+    let t = self.easing.apply(progress);
+    if t >= 1.0 {
+      return self.to;
+    }
+
+    let center = self.from.center + (self.to.center - self.from.center) * t;
+    let from_distance = (self.from.eye - self.from.center).magnitude();
+    let to_distance = (self.to.eye - self.to.center).magnitude();
+    let distance = from_distance * (to_distance / from_distance).powf(t);
+
+    let orientation = quat_slerp(&self.from.orientation(), &self.to.orientation(), t);
+    let forward = quat_rotate_vec3(&orientation, &Vec3::new(0.0, 0.0, -1.0));
+    let up = quat_rotate_vec3(&orientation, &Vec3::new(0.0, 1.0, 0.0));
+
+    CameraPose {
+      eye: center - forward * distance,
+      center,
+      up,
+      fov: self.from.fov + (self.to.fov - self.from.fov) * t,
+    }
+  }
+
+  // Advances by `dt` seconds and moves the camera. Returns true once the camera has arrived
+  pub fn update(&mut self, dt: f32, camera: &mut Camera) -> bool {
+    self.elapsed += dt;
+    let progress = if self.duration > 0.0 { self.elapsed / self.duration } else { 1.0 };
+    self.pose_at(progress).apply(camera);
+    self.is_finished()
+  }
+}
@@ -3,11 +3,12 @@ use crate::fragment::Fragment;
 use crate::vertex::Vertex;
 use crate::color::Color;
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
+// Fragments of the triangle that fall inside a `width` x `height` framebuffer
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, width: usize, height: usize) -> Vec<Fragment> {
   let mut fragments = Vec::new();
   let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
 
-  let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
+  let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c, width, height);
 
   let light_dir = Vec3::new(0.0, 0.0, 1.0);
 
@@ -22,9 +23,9 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
       let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
 
       // Check if the point is inside the triangle
-      if w1 >= 0.0 && w1 <= 1.0 && 
-         w2 >= 0.0 && w2 <= 1.0 &&
-         w3 >= 0.0 && w3 <= 1.0 {
+      if (0.0..=1.0).contains(&w1) &&
+         (0.0..=1.0).contains(&w2) &&
+         (0.0..=1.0).contains(&w3) {
 
         // Interpolate normal
         let normal = v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3;
@@ -41,6 +42,11 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
 
         // Positions of the original vertex
         let vertex_position = v1.position * w1 + v2.position * w2 + v3.position * w3;
+        let world_position = v1.world_position * w1 + v2.world_position * w2 + v3.world_position * w3;
+
+        // Tangent basis for normal mapping, left unnormalized; shaders that use it normalize it
+        let tangent = v1.transformed_tangent * w1 + v2.transformed_tangent * w2 + v3.transformed_tangent * w3;
+        let bitangent = v1.transformed_bitangent * w1 + v2.transformed_bitangent * w2 + v3.transformed_bitangent * w3;
 
         fragments.push(Fragment::new(
             Vec2::new(x as f32, y as f32),
@@ -49,7 +55,10 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
             normal,
             intensity,
             vertex_position,
-            None
+            world_position,
+            None,
+            tangent,
+            bitangent,
         ));
       }
     }
@@ -58,11 +67,12 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
   fragments
 }
 
-fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
-    let min_x = v1.x.min(v2.x).min(v3.x).floor() as i32;
-    let min_y = v1.y.min(v2.y).min(v3.y).floor() as i32;
-    let max_x = v1.x.max(v2.x).max(v3.x).ceil() as i32;
-    let max_y = v1.y.max(v2.y).max(v3.y).ceil() as i32;
+// Clamped to the framebuffer, so a triangle reaching far off screen costs no more than the screen
+fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3, width: usize, height: usize) -> (i32, i32, i32, i32) {
+    let min_x = (v1.x.min(v2.x).min(v3.x).floor() as i32).max(0);
+    let min_y = (v1.y.min(v2.y).min(v3.y).floor() as i32).max(0);
+    let max_x = (v1.x.max(v2.x).max(v3.x).ceil() as i32).min(width as i32 - 1);
+    let max_y = (v1.y.max(v2.y).max(v3.y).ceil() as i32).min(height as i32 - 1);
 
     (min_x, min_y, max_x, max_y)
 }
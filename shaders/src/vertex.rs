@@ -1,25 +1,41 @@
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::{Vec2, Vec3, Vec4};
 use crate::color::Color;
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
   pub position: Vec3,
   pub normal: Vec3,
+  // Directions of increasing u and v on the surface, orthogonal to the normal (for normal mapping)
+  pub tangent: Vec3,
+  pub bitangent: Vec3,
   pub tex_coords: Vec2,
   pub color: Color,
   pub transformed_position: Vec3,
+  // Position after the projection and before the perspective division, for near-plane clipping
+  pub clip_position: Vec4,
   pub transformed_normal: Vec3,
+  pub transformed_tangent: Vec3,
+  pub transformed_bitangent: Vec3,
+  pub world_position: Vec3,
 }
 
 impl Vertex {
+  // Without texture coordinates to follow, the tangents are any basis around the normal
   pub fn new(position: Vec3, normal: Vec3, tex_coords: Vec2) -> Self {
+    let (tangent, bitangent) = tangent_basis(&normal);
     Vertex {
       position,
       normal,
+      tangent,
+      bitangent,
       tex_coords,
       color: Color::black(),
       transformed_position: position,
+      clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
       transformed_normal: normal,
+      transformed_tangent: tangent,
+      transformed_bitangent: bitangent,
+      world_position: position,
     }
   }
 
@@ -27,10 +43,16 @@ impl Vertex {
     Vertex {
       position,
       normal: Vec3::new(0.0, 0.0, 0.0),
+      tangent: Vec3::new(0.0, 0.0, 0.0),
+      bitangent: Vec3::new(0.0, 0.0, 0.0),
       tex_coords: Vec2::new(0.0, 0.0),
       color,
       transformed_position: Vec3::new(0.0, 0.0, 0.0),
+      clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
       transformed_normal: Vec3::new(0.0, 0.0, 0.0),
+      transformed_tangent: Vec3::new(0.0, 0.0, 0.0),
+      transformed_bitangent: Vec3::new(0.0, 0.0, 0.0),
+      world_position: position,
     }
   }
 
@@ -45,10 +67,28 @@ impl Default for Vertex {
     Vertex {
       position: Vec3::new(0.0, 0.0, 0.0),
       normal: Vec3::new(0.0, 1.0, 0.0),
+      tangent: Vec3::new(1.0, 0.0, 0.0),
+      bitangent: Vec3::new(0.0, 0.0, -1.0),
       tex_coords: Vec2::new(0.0, 0.0),
       color: Color::black(),
       transformed_position: Vec3::new(0.0, 0.0, 0.0),
+      clip_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
       transformed_normal: Vec3::new(0.0, 1.0, 0.0),
+      transformed_tangent: Vec3::new(1.0, 0.0, 0.0),
+      transformed_bitangent: Vec3::new(0.0, 0.0, -1.0),
+      world_position: Vec3::new(0.0, 0.0, 0.0),
     }
   }
+}
+
+// Two unit vectors that complete an orthonormal basis with the normal, with
+// bitangent = normal x tangent. Uses the branchless construction of Duff et al. (2017)
+pub fn tangent_basis(normal: &Vec3) -> (Vec3, Vec3) {
+  let n = if normal.magnitude_squared() > 0.0 { normal.normalize() } else { Vec3::new(0.0, 0.0, 1.0) };
+  let sign = 1.0f32.copysign(n.z);
+  let a = -1.0 / (sign + n.z);
+  let b = n.x * n.y * a;
+  let tangent = Vec3::new(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x);
+  let bitangent = Vec3::new(b, sign + n.y * n.y * a, -n.y);
+  (tangent, bitangent)
 }
\ No newline at end of file
@@ -0,0 +1,52 @@
+use nalgebra_glm::{Vec3, Vec4};
+use std::f32::consts::PI;
+use shaders::body::{self, Body};
+use shaders::render::create_model_matrix;
+
+// Venus is not drawn, but its retrograde spin pins down the convention for tilts past 90°
+const VENUS: Body = Body {
+  name: "Venus",
+  axial_tilt: 177.36 * PI / 180.0,
+  rotation_period: 243.0226,
+  prime_meridian: 160.20 * PI / 180.0,
+};
+
+// Angular velocity of the body in scene space, scaled by the step: a point on the equator
+// crossed with where it is a moment later
+fn spin(body: &Body) -> Vec3 {
+  let at = |days: f32| {
+    let p = create_model_matrix(Vec3::zeros(), 1.0, body.rotation(days)) * Vec4::new(1.0, 0.0, 0.0, 1.0);
+    Vec3::new(p.x, p.y, p.z)
+  };
+  let step = body.rotation_period * 0.01;
+  at(0.0).cross(&at(step))
+}
+
+#[test]
+fn spin_angle_wraps_once_per_period() {
+  let earth = body::EARTH;
+  assert!((earth.spin_angle(0.0) - earth.prime_meridian).abs() < 1e-5);
+  let later = earth.spin_angle(earth.rotation_period * 3.0);
+  assert!((later - earth.prime_meridian).abs() < 1e-3, "{}", later);
+  for days in [0.0, 0.4, 17.0, -5.0] {
+    let angle = earth.spin_angle(days);
+    assert!((0.0..2.0 * PI).contains(&angle), "{}", angle);
+  }
+}
+
+#[test]
+fn venus_and_uranus_spin_against_earth() {
+  // Orbits run counterclockwise around scene +Y, the ecliptic north
+  let north = Vec3::y();
+  assert!(spin(&body::EARTH).dot(&north) > 0.0);
+  assert!(spin(&body::MARS).dot(&north) > 0.0);
+  assert!(spin(&VENUS).dot(&north) < 0.0);
+  assert!(spin(&body::URANUS).dot(&north) < 0.0);
+
+  // The axis itself is tilted past 90° and the spin goes forward around it
+  let axis = |body: &Body| create_model_matrix(Vec3::zeros(), 1.0, body.rotation(0.0)) * Vec4::new(0.0, 1.0, 0.0, 0.0);
+  for body in [body::EARTH, VENUS, body::URANUS] {
+    let axis = axis(&body);
+    assert!(spin(&body).dot(&Vec3::new(axis.x, axis.y, axis.z)) > 0.0, "{}", body.name);
+  }
+}
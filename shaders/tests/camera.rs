@@ -0,0 +1,125 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::{Camera, CameraMode};
+use shaders::render::create_view_matrix;
+
+fn view_of(camera: &Camera) -> nalgebra_glm::Mat4 {
+    create_view_matrix(camera.eye, camera.center, camera.up)
+}
+
+#[test]
+fn toggling_free_fly_preserves_the_view() {
+    let mut camera = Camera::new(Vec3::new(3.0, 2.0, 10.0), Vec3::new(0.5, 0.0, 0.0), Vec3::new(0.2, 1.0, 0.0));
+    let before = view_of(&camera);
+
+    camera.toggle_mode();
+    assert_eq!(camera.mode, CameraMode::FreeFly);
+    assert!((view_of(&camera) - before).abs().max() < 1e-4);
+
+    camera.toggle_mode();
+    assert_eq!(camera.mode, CameraMode::Orbit);
+    assert!((view_of(&camera) - before).abs().max() < 1e-4);
+}
+
+#[test]
+fn toggling_free_fly_while_looking_down_a_pole_preserves_the_view() {
+    for (eye, up) in [(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, 0.0, -1.0)), (Vec3::new(0.0, -10.0, 0.0), Vec3::new(0.3, 0.0, 1.0))] {
+        let mut camera = Camera::new(eye, Vec3::new(0.0, 0.0, 0.0), up);
+        let (before, forward) = (view_of(&camera), camera.forward());
+
+        camera.toggle_mode();
+        assert_eq!(view_of(&camera), before);
+        // Sin girar, la vista sigue exactamente igual
+        camera.look(0.0, 0.0, 0.0);
+        assert!((camera.forward() - forward).magnitude() < 1e-6);
+        assert!((view_of(&camera) - before).abs().max() < 1e-5);
+
+        camera.toggle_mode();
+        assert!((view_of(&camera) - before).abs().max() < 1e-5);
+    }
+}
+
+#[test]
+fn free_fly_pitch_stops_at_the_pole_but_turns_back_from_it() {
+    let mut camera = Camera::new(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+    camera.set_mode(CameraMode::FreeFly);
+
+    // Ya mira hacia abajo del todo: seguir bajando no cambia nada
+    camera.look(0.0, -0.2, 0.0);
+    assert!((camera.forward() - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+
+    // Subir la lleva hacia el horizonte, por donde apuntaba su `up`
+    camera.look(0.0, 0.2, 0.0);
+    assert!((camera.forward().y + 0.2f32.cos()).abs() < 1e-4);
+    assert!(camera.forward().z < 0.0);
+
+    // Desde el horizonte no pasa del límite
+    let mut camera = Camera::default();
+    camera.set_mode(CameraMode::FreeFly);
+    camera.look(0.0, 3.0, 0.0);
+    assert!(camera.forward().y < 1.0 - 1e-5);
+    assert!((camera.up.dot(&camera.forward())).abs() < 1e-5);
+}
+
+#[test]
+fn free_fly_look_turns_by_yaw_pitch_and_roll() {
+    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    camera.set_mode(CameraMode::FreeFly);
+    camera.look(0.3, 0.2, 0.1);
+
+    let expected = Vec3::new(0.3f32.sin() * 0.2f32.cos(), 0.2f32.sin(), -0.3f32.cos() * 0.2f32.cos());
+    assert!((camera.forward() - expected).magnitude() < 1e-5);
+    assert!((camera.yaw - 0.3).abs() < 1e-5 && (camera.pitch - 0.2).abs() < 1e-5 && (camera.roll - 0.1).abs() < 1e-5);
+}
+
+#[test]
+fn free_fly_moves_along_the_view_direction() {
+    let mut camera = Camera::default();
+    camera.set_mode(CameraMode::FreeFly);
+    let forward = camera.forward();
+    let eye = camera.eye;
+
+    camera.fly(Vec3::new(0.0, 0.0, 2.0));
+    assert!((camera.eye - (eye + forward * 2.0)).magnitude() < 1e-5);
+    assert!((camera.forward() - forward).magnitude() < 1e-5);
+}
+
+#[test]
+fn orbit_goes_over_the_pole_with_an_orthonormal_basis() {
+    let mut camera = Camera::default();
+    let distance = (camera.eye - camera.center).magnitude();
+
+    // Media vuelta en pitch pasa por encima del polo sin detenerse
+    for _ in 0..100 {
+        camera.orbit(0.0, -std::f32::consts::PI / 100.0);
+    }
+    assert!(camera.eye.z < -9.0, "eye {:?}", camera.eye);
+    assert!(camera.up.y < 0.0);
+
+    camera.roll(0.3);
+    camera.orbit(0.7, 0.2);
+    assert!(((camera.eye - camera.center).magnitude() - distance).abs() < 1e-3);
+    assert!((camera.up.magnitude() - 1.0).abs() < 1e-5);
+    assert!(camera.up.dot(&camera.forward()).abs() < 1e-5);
+}
+
+#[test]
+fn orbit_velocity_eases_in_and_out() {
+    let mut camera = Camera::default();
+    camera.set_orbit_velocity(Vec3::new(1.0, 0.0, 0.0));
+    camera.update(1.0 / 60.0);
+    assert!(camera.orbit_velocity.x > 0.0 && camera.orbit_velocity.x < 1.0);
+
+    for _ in 0..120 {
+        camera.update(1.0 / 60.0);
+    }
+    assert!((camera.orbit_velocity.x - 1.0).abs() < 1e-3);
+
+    camera.set_orbit_velocity(Vec3::zeros());
+    let eye = camera.eye;
+    camera.update(1.0 / 60.0);
+    assert!(camera.eye != eye, "the camera should coast after release");
+    for _ in 0..300 {
+        camera.update(1.0 / 60.0);
+    }
+    assert_eq!(camera.orbit_velocity, Vec3::zeros());
+}
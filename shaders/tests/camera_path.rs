@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::camera_path::{CameraPath, Interpolation, KEYFRAME_SPACING};
+use shaders::record::{record, RecordFormat, RecordSettings};
+use shaders::scene::Scene;
+use shaders::transition::CameraPose;
+
+fn pose(x: f32, fov: f32) -> CameraPose {
+    CameraPose {
+        eye: Vec3::new(x, 2.0, 10.0),
+        center: Vec3::new(x * 0.5, 0.0, 0.0),
+        up: Vec3::new(0.0, 1.0, 0.0),
+        fov,
+    }
+}
+
+fn sample_path(interpolation: Interpolation) -> CameraPath {
+    let mut path = CameraPath::new(interpolation);
+    path.add(pose(0.0, 0.8), 0.0);
+    path.add(pose(4.0, 0.6), 10.0);
+    path.add(pose(-2.0, 0.7), 15.0);
+    path.add(pose(1.0, 0.8), 30.0);
+    path
+}
+
+#[test]
+fn catmull_rom_passes_through_every_keyframe() {
+    let path = sample_path(Interpolation::CatmullRom);
+    assert_eq!(path.duration(), 3.0 * KEYFRAME_SPACING);
+
+    for keyframe in &path.keyframes {
+        let (pose, time) = path.sample(keyframe.at).unwrap();
+        assert!((pose.eye - keyframe.pose.eye).magnitude() < 1e-4);
+        assert!((pose.fov - keyframe.pose.fov).abs() < 1e-5);
+        assert!((time - keyframe.time).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn bezier_hits_only_the_ends() {
+    let path = sample_path(Interpolation::Bezier);
+    let (start, _) = path.sample(0.0).unwrap();
+    let (end, time) = path.sample(path.duration() + 1.0).unwrap();
+    assert!((start.eye - path.keyframes[0].pose.eye).magnitude() < 1e-5);
+    assert!((end.eye - path.keyframes[3].pose.eye).magnitude() < 1e-5);
+    assert!((time - 30.0).abs() < 1e-4);
+
+    let (middle, _) = path.sample(path.keyframes[1].at).unwrap();
+    assert!((middle.eye - path.keyframes[1].pose.eye).magnitude() > 0.1);
+}
+
+#[test]
+fn path_survives_a_text_roundtrip() {
+    let path = sample_path(Interpolation::Bezier);
+    assert_eq!(CameraPath::parse(&path.to_text()).unwrap(), path);
+
+    let error = CameraPath::parse("interpolation catmull-rom\n0 0 1 2 3\n").unwrap_err();
+    assert!(error.starts_with("line 2"), "{}", error);
+}
+
+#[test]
+fn recording_follows_the_camera_path() {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("camera_path");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+
+    let file = directory.join("path.txt");
+    let mut path = CameraPath::new(Interpolation::CatmullRom);
+    path.add(CameraPose::of(&Camera::default()), 0.0);
+    path.add(pose(3.0, 0.7), 5.0);
+    path.save(&file).unwrap();
+
+    let mut settings = RecordSettings::new(RecordFormat::Png);
+    settings.output = directory.join("frames");
+    settings.camera_path = Some(file);
+    settings.fps = 2;
+    settings.width = 34;
+    settings.height = 40;
+
+    let scene = Scene::load().unwrap();
+    let frames = record(&scene, &Camera::default(), &settings).unwrap();
+    assert_eq!(frames, (KEYFRAME_SPACING * 2.0) as usize + 1);
+    assert_eq!(fs::read_dir(&settings.output).unwrap().count(), frames);
+}
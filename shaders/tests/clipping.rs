@@ -0,0 +1,41 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::framebuffer::Framebuffer;
+use shaders::scene::{render_headless, LodState, Scene, BACKGROUND_COLOR};
+
+const WIDTH: usize = 68;
+const HEIGHT: usize = 80;
+
+fn background_pixels(framebuffer: &Framebuffer) -> usize {
+    framebuffer.buffer.iter().filter(|&&color| color == BACKGROUND_COLOR).count()
+}
+
+#[test]
+fn the_eye_inside_a_body_sees_its_inner_wall() {
+    let scene = Scene::procedural();
+    let camera = Camera::new(Vec3::new(0.0, 0.0, 0.9), Vec3::zeros(), Vec3::y());
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    let profile = scene.render(&mut framebuffer, &camera, 1, 2.0, &mut LodState::new());
+
+    // La pared del fondo llena la vista y los triángulos que cruzan el plano cercano se recortan
+    // en lugar de proyectarse sobre toda la pantalla
+    assert_eq!(background_pixels(&framebuffer), 0);
+    let geometry = profile.geometry();
+    assert!(geometry.fragments < geometry.triangles * WIDTH * HEIGHT / 16, "{} {}", geometry.fragments, geometry.triangles);
+    assert!(framebuffer.zbuffer.iter().all(|depth| (-1.0..=1.0).contains(depth)));
+}
+
+#[test]
+fn bodies_behind_the_camera_are_not_drawn() {
+    let scene = Scene::procedural();
+    let facing = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::y());
+    let away = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 10.0), Vec3::y());
+
+    let seen = render_headless(&scene, &facing, 7, 2.0, WIDTH, HEIGHT);
+    assert!(background_pixels(&seen) < WIDTH * HEIGHT);
+    // Antes el Sol aparecía reflejado con los mismos píxeles encendidos que de frente
+    let behind = render_headless(&scene, &away, 7, 2.0, WIDTH, HEIGHT);
+    assert_eq!(background_pixels(&behind), WIDTH * HEIGHT);
+    assert!(behind.emissive_buffer.iter().all(|&emission| emission == 0));
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+use shaders::clock::SimClock;
+
+fn seconds(s: f64) -> Duration {
+  Duration::from_secs_f64(s)
+}
+
+#[test]
+fn pausing_freezes_time() {
+  let mut clock = SimClock::new();
+  clock.tick(seconds(0.5));
+  clock.toggle_pause();
+  assert!(clock.is_paused());
+  clock.tick(seconds(3.0));
+  assert_eq!(clock.time(), 0.5);
+
+  clock.toggle_pause();
+  clock.tick(seconds(0.25));
+  assert_eq!(clock.time(), 0.75);
+}
+
+#[test]
+fn reverse_runs_time_backwards_at_the_same_speed() {
+  let mut clock = SimClock::new();
+  clock.set_speed(4.0);
+  clock.tick(seconds(1.0));
+  clock.toggle_reverse();
+  assert!(clock.is_reversed());
+  clock.tick(seconds(0.25));
+  assert_eq!(clock.time(), 3.0);
+  // Time can go before the epoch
+  clock.tick(seconds(1.0));
+  assert_eq!(clock.time(), -1.0);
+}
+
+#[test]
+fn jumps_move_time_regardless_of_pause_and_speed() {
+  let mut clock = SimClock::new();
+  clock.set_speed(100.0);
+  clock.toggle_pause();
+  clock.jump_to(42.0);
+  assert_eq!(clock.time(), 42.0);
+  clock.jump_by(-2.5);
+  assert_eq!(clock.time(), 39.5);
+  clock.jump_by(10.0);
+  assert_eq!(clock.time(), 49.5);
+}
+
+#[test]
+fn speed_is_clamped() {
+  let mut clock = SimClock::new();
+  for _ in 0..40 {
+    clock.speed_up();
+  }
+  assert_eq!(clock.speed(), 65536.0);
+  for _ in 0..40 {
+    clock.slow_down();
+  }
+  assert_eq!(clock.speed(), 1.0 / 64.0);
+
+  clock.set_speed(1e9);
+  assert_eq!(clock.speed(), 65536.0);
+  clock.set_speed(0.0);
+  assert_eq!(clock.speed(), 1.0 / 64.0);
+  clock.set_speed(-3.0);
+  assert_eq!(clock.speed(), 1.0 / 64.0);
+}
+
+#[test]
+fn fixed_timestep_does_not_depend_on_the_frame_rate() {
+  let step = 1.0 / 60.0;
+  let mut single = SimClock::new();
+  let mut split = SimClock::new();
+  for clock in [&mut single, &mut split] {
+    clock.set_fixed_timestep(Some(step));
+    clock.set_speed(2.0);
+  }
+
+  single.tick(seconds(1.0));
+  for _ in 0..10 {
+    split.tick(seconds(0.1));
+  }
+  assert_eq!(single.time(), split.time());
+  assert!((single.time() - 2.0).abs() < 1e-4, "{}", single.time());
+
+  // A frame shorter than a step only fills the accumulator
+  let mut clock = SimClock::new();
+  clock.set_fixed_timestep(Some(step));
+  clock.tick(seconds(step * 0.6));
+  assert_eq!(clock.time(), 0.0);
+  clock.tick(seconds(step * 0.6));
+  assert!((clock.time() as f64 - step).abs() < 1e-6);
+
+  // Non-positive steps turn the fixed timestep off
+  clock.set_fixed_timestep(Some(0.0));
+  assert_eq!(clock.fixed_timestep(), None);
+}
@@ -0,0 +1,42 @@
+// Cada prueba usa sólo parte de lo que hay aquí
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use nalgebra_glm::{mat4_to_mat3, Mat4, Vec2, Vec3};
+use shaders::camera::Camera;
+use shaders::color::Color;
+use shaders::fragment::Fragment;
+use shaders::render::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use shaders::Uniforms;
+
+// Uniforms del shader vistos con la cámara por defecto en un viewport de `width` x `height`
+pub fn camera_uniforms(shader: u8, model_matrix: Mat4, width: f32, height: f32) -> Uniforms {
+    let camera = Camera::default();
+    Uniforms {
+        view_matrix: create_view_matrix(camera.eye, camera.center, camera.up),
+        projection_matrix: create_projection_matrix(&camera, width, height, 0.1, 1000.0),
+        viewport_matrix: create_viewport_matrix(width, height),
+        time: 1.0,
+        ..Uniforms::for_shader(shader, model_matrix)
+    }
+}
+
+// Fragmento sobre la esfera unitaria en la dirección `point`, ya llevado al mundo con la matriz de
+// modelo de `uniforms`
+pub fn fragment_at(point: Vec3, uniforms: &Uniforms) -> Fragment {
+    let model = mat4_to_mat3(&uniforms.model_matrix);
+    let normal = (model * point).normalize();
+    Fragment::new(Vec2::new(0.0, 0.0), Color::black(), 0.0, normal, 1.0, point, model * point, None, Vec3::x(), Vec3::y())
+}
+
+// Escribe los archivos de un modelo en su propio directorio temporal `name` y devuelve la ruta del
+// primero, que es el OBJ
+pub fn write_model(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("obj").join(name);
+    fs::create_dir_all(&directory).unwrap();
+    for (file, contents) in files {
+        fs::write(directory.join(file), contents).unwrap();
+    }
+    directory.join(files[0].0)
+}
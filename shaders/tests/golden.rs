@@ -0,0 +1,142 @@
+// Pruebas de regresión visual: cada planeta se renderiza sin ventana con parámetros fijos
+// y se compara contra una imagen de referencia en tests/golden.
+// Para regenerar las referencias: UPDATE_GOLDEN=1 cargo test --test golden
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::image_io::{load_png, save_png};
+use shaders::scene::{render_headless, Scene};
+
+// Un cuarto de la ventana, con la misma relación de aspecto
+const WIDTH: usize = 170;
+const HEIGHT: usize = 200;
+const TIME: f32 = 12.5;
+
+// Diferencia perceptual máxima por píxel (0 a 1) y fracción de píxeles que puede superarla
+const PIXEL_TOLERANCE: f32 = 0.04;
+const MAX_BAD_PIXELS: f32 = 0.002;
+
+// La misma cámara inicial que usa el visor
+fn planet_camera() -> Camera {
+    Camera::new(Vec3::new(0.0, 2.0, 10.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 10.0, 0.0))
+}
+
+fn system_camera() -> Camera {
+    Camera::new(Vec3::new(0.0, 12.0, 18.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0))
+}
+
+fn reference_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.png", name))
+}
+
+// Distancia en YCbCr, dando más peso a la luminancia que al color
+fn perceptual_difference(a: u32, b: u32) -> f32 {
+    let ycbcr = |c: u32| {
+        let r = ((c >> 16) & 0xFF) as f32 / 255.0;
+        let g = ((c >> 8) & 0xFF) as f32 / 255.0;
+        let b = (c & 0xFF) as f32 / 255.0;
+        let y = 0.299 * r + 0.587 * g + 0.114 * b;
+        (y, (b - y) * 0.564, (r - y) * 0.713)
+    };
+    let (y1, cb1, cr1) = ycbcr(a);
+    let (y2, cb2, cr2) = ycbcr(b);
+    ((y1 - y2).powi(2) + 0.5 * ((cb1 - cb2).powi(2) + (cr1 - cr2).powi(2))).sqrt()
+}
+
+fn check_golden(name: &str, planet: u8, camera: &Camera) {
+    let scene = Scene::load().expect("Failed to load obj");
+    let framebuffer = render_headless(&scene, camera, planet, TIME, WIDTH, HEIGHT);
+    let reference = reference_path(name);
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        save_png(&reference, WIDTH, HEIGHT, &framebuffer.buffer).unwrap();
+        return;
+    }
+
+    let (width, height, expected) = load_png(&reference)
+        .unwrap_or_else(|err| panic!("missing reference {}: {} (run with UPDATE_GOLDEN=1)", reference.display(), err));
+    assert_eq!((width, height), (WIDTH, HEIGHT), "reference {} has a different size", name);
+
+    let mut bad_pixels = 0;
+    let diff: Vec<u32> = framebuffer.buffer.iter().zip(&expected).map(|(&actual, &expected)| {
+        if perceptual_difference(actual, expected) > PIXEL_TOLERANCE {
+            bad_pixels += 1;
+            0xFF0000
+        } else {
+            // Píxeles iguales en gris tenue para dar contexto
+            let luma = (((actual >> 16) & 0xFF) + ((actual >> 8) & 0xFF) + (actual & 0xFF)) / 9;
+            (luma << 16) | (luma << 8) | luma
+        }
+    }).collect();
+
+    let bad_fraction = bad_pixels as f32 / (WIDTH * HEIGHT) as f32;
+    if bad_fraction > MAX_BAD_PIXELS {
+        let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        fs::create_dir_all(&output).unwrap();
+        save_png(&output.join(format!("{}.actual.png", name)), WIDTH, HEIGHT, &framebuffer.buffer).unwrap();
+        save_png(&output.join(format!("{}.diff.png", name)), WIDTH, HEIGHT, &diff).unwrap();
+        panic!(
+            "{}: {:.2}% of pixels differ from the reference, see {}",
+            name, bad_fraction * 100.0, output.display()
+        );
+    }
+}
+
+#[test]
+fn earth_matches_reference() {
+    check_golden("earth", 1, &planet_camera());
+}
+
+#[test]
+fn mars_matches_reference() {
+    check_golden("mars", 2, &planet_camera());
+}
+
+#[test]
+fn mercury_matches_reference() {
+    check_golden("mercury", 3, &planet_camera());
+}
+
+#[test]
+fn saturn_matches_reference() {
+    check_golden("saturn", 4, &planet_camera());
+}
+
+#[test]
+fn jupiter_matches_reference() {
+    check_golden("jupiter", 5, &planet_camera());
+}
+
+#[test]
+fn uranus_matches_reference() {
+    check_golden("uranus", 6, &planet_camera());
+}
+
+#[test]
+fn sun_matches_reference() {
+    check_golden("sun", 7, &planet_camera());
+}
+
+#[test]
+fn system_matches_reference() {
+    check_golden("system", 0, &system_camera());
+}
+
+#[test]
+fn orthographic_saturn_matches_reference() {
+    let mut camera = planet_camera();
+    camera.toggle_projection();
+    check_golden("saturn-orthographic", 4, &camera);
+}
+
+#[test]
+fn rendering_is_deterministic() {
+    let scene = Scene::load().expect("Failed to load obj");
+    let first = render_headless(&scene, &planet_camera(), 5, TIME, WIDTH, HEIGHT);
+    let second = render_headless(&scene, &planet_camera(), 5, TIME, WIDTH, HEIGHT);
+    assert_eq!(first.buffer, second.buffer);
+    assert_eq!(first.emissive_buffer, second.emissive_buffer);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use nalgebra_glm::Vec3;
+use shaders::framebuffer::Framebuffer;
+use shaders::obj::Obj;
+use shaders::render::{create_model_matrix, draw_indexed, render};
+use shaders::Uniforms;
+use common::camera_uniforms;
+
+const WIDTH: usize = 85;
+const HEIGHT: usize = 100;
+
+fn earth_uniforms() -> Uniforms {
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.4, 0.0));
+    camera_uniforms(1, model_matrix, WIDTH as f32, HEIGHT as f32)
+}
+
+fn framebuffer() -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.clear();
+    framebuffer
+}
+
+#[test]
+fn indexed_drawing_matches_the_flat_path() {
+    let sphere = Obj::load("assets/models/sphere.obj").unwrap();
+    let indexed = sphere.get_indexed_mesh();
+    let flat = sphere.get_vertex_array();
+    assert_eq!(indexed.get_vertex_array().len(), flat.len());
+    let uniforms = earth_uniforms();
+
+    let mut expected = framebuffer();
+    let flat_stats = render(&mut expected, &uniforms, &flat, 1.0);
+    let mut actual = framebuffer();
+    let indexed_stats = draw_indexed(&mut actual, &uniforms, &indexed, 1.0);
+
+    assert_eq!(actual.buffer, expected.buffer);
+    assert_eq!(actual.emissive_buffer, expected.emissive_buffer);
+    assert_eq!(indexed_stats.triangles, flat_stats.triangles);
+    assert_eq!(indexed_stats.shaded, flat_stats.shaded);
+
+    // Cada vértice único se transforma una sola vez
+    assert_eq!(flat_stats.vertices, flat.len());
+    assert_eq!(indexed_stats.vertices, indexed.vertices.len());
+    assert!(indexed_stats.vertices * 5 < flat_stats.vertices);
+}
+
+#[test]
+fn unused_vertices_are_not_shaded() {
+    let sphere = Obj::load("assets/models/sphere.obj").unwrap();
+    let mut indexed = sphere.get_indexed_mesh();
+    indexed.indices.truncate(3);
+
+    let stats = draw_indexed(&mut framebuffer(), &earth_uniforms(), &indexed, 1.0);
+    assert_eq!(stats.vertices, 3);
+    assert_eq!(stats.triangles, 1);
+}
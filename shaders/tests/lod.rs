@@ -0,0 +1,111 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::{Camera, Projection};
+use shaders::framebuffer::Framebuffer;
+use shaders::mesh::{LodMesh, LOD_HYSTERESIS};
+use shaders::primitives::icosphere;
+use shaders::render::projected_radius;
+use shaders::scene::{render_headless, LodState, Scene, BACKGROUND_COLOR};
+
+fn three_levels() -> LodMesh {
+    LodMesh::new(icosphere(3).get_indexed_mesh())
+        .with_level(icosphere(2).get_indexed_mesh(), 40.0)
+        .with_level(icosphere(1).get_indexed_mesh(), 10.0)
+}
+
+fn planet_lod(scene: &Scene, camera: &Camera, state: &mut LodState) -> usize {
+    let mut framebuffer = Framebuffer::new(160, 120);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    let profile = scene.render(&mut framebuffer, camera, 2, 1.0, state);
+    profile.objects.iter().find(|object| object.name == "Mars").unwrap().lod
+}
+
+// Cámara con Marte un poco por encima del umbral de 6 píxeles, dentro de la banda de histéresis
+fn camera_in_the_hysteresis_band() -> Camera {
+    let radius = 6.0 * (1.0 + LOD_HYSTERESIS / 2.0);
+    let fov = Camera::default().fov;
+    let distance = 120.0 / 2.0 / (radius * (fov / 2.0).tan());
+    Camera::new(Vec3::new(0.0, 0.0, distance), Vec3::zeros(), Vec3::y())
+}
+
+#[test]
+fn selection_waits_past_the_hysteresis_band() {
+    let lod = three_levels();
+    assert_eq!(lod.len(), 3);
+    assert_eq!(lod.select(100.0, None), 0);
+    assert_eq!(lod.select(39.0, None), 1);
+    assert_eq!(lod.select(5.0, None), 2);
+
+    // Justo debajo del umbral se queda en el nivel anterior, pasada la histéresis cambia
+    assert_eq!(lod.select(39.0, Some(0)), 0);
+    assert_eq!(lod.select(40.0 * (1.0 - LOD_HYSTERESIS) - 0.1, Some(0)), 1);
+    assert_eq!(lod.select(41.0, Some(1)), 1);
+    assert_eq!(lod.select(40.0 * (1.0 + LOD_HYSTERESIS) + 0.1, Some(1)), 0);
+    // Un salto grande cruza varios niveles de una vez
+    assert_eq!(lod.select(1.0, Some(0)), 2);
+}
+
+#[test]
+fn projected_radius_shrinks_with_distance() {
+    let near = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::y());
+    let far = Camera::new(Vec3::new(0.0, 0.0, 50.0), Vec3::zeros(), Vec3::y());
+    let near_radius = projected_radius(&near, Vec3::zeros(), 1.0, 600.0);
+    let far_radius = projected_radius(&far, Vec3::zeros(), 1.0, 600.0);
+    assert!((near_radius / far_radius - 10.0).abs() < 1e-3);
+
+    // Desde adentro la esfera llena la pantalla
+    assert_eq!(projected_radius(&near, Vec3::new(0.0, 0.0, 5.5), 1.0, 600.0), f32::INFINITY);
+
+    // En ortográfica no importa la distancia al objeto sino el encuadre
+    let mut ortho = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::y());
+    ortho.projection = Projection::Orthographic;
+    let side = projected_radius(&ortho, Vec3::new(0.0, 0.0, 4.0), 1.0, 600.0);
+    assert_eq!(side, projected_radius(&ortho, Vec3::zeros(), 1.0, 600.0));
+}
+
+#[test]
+fn distant_objects_are_drawn_with_coarser_levels() {
+    let scene = Scene::procedural();
+    let near = Camera::new(Vec3::new(0.0, 0.0, 3.0), Vec3::zeros(), Vec3::y());
+    let far = Camera::new(Vec3::new(0.0, 0.0, 60.0), Vec3::zeros(), Vec3::y());
+
+    let mut framebuffer = Framebuffer::new(160, 120);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    let close_up = scene.render(&mut framebuffer, &near, 2, 1.0, &mut LodState::new());
+    let distant = scene.render(&mut framebuffer, &far, 2, 1.0, &mut LodState::new());
+
+    // El perfil dice qué nivel se usó y el más lejano dibuja menos triángulos
+    let mars = |profile: &shaders::profiler::FrameProfile| {
+        profile.objects.iter().find(|object| object.name == "Mars").unwrap().clone()
+    };
+    let (close_up, distant) = (mars(&close_up), mars(&distant));
+    assert_eq!(close_up.lod, 0);
+    assert!(distant.lod > 0);
+    assert!(distant.stats.triangles < close_up.stats.triangles);
+}
+
+#[test]
+fn the_lod_state_remembers_the_previous_level() {
+    let scene = Scene::procedural();
+    let far = Camera::new(Vec3::new(0.0, 0.0, 60.0), Vec3::zeros(), Vec3::y());
+    let mut state = LodState::new();
+    assert_eq!(planet_lod(&scene, &far, &mut state), 3);
+    assert_eq!(state.level("Mars"), Some(3));
+
+    let camera = camera_in_the_hysteresis_band();
+    assert_eq!(planet_lod(&scene, &camera, &mut state), 3);
+    assert_eq!(planet_lod(&scene, &camera, &mut state), 3);
+    // Sin cuadros anteriores la misma cámara ya usa el nivel siguiente
+    assert_eq!(planet_lod(&scene, &camera, &mut LodState::new()), 2);
+}
+
+#[test]
+fn headless_frames_do_not_depend_on_earlier_frames() {
+    let scene = Scene::procedural();
+    let far = Camera::new(Vec3::new(0.0, 0.0, 60.0), Vec3::zeros(), Vec3::y());
+    let camera = camera_in_the_hysteresis_band();
+
+    let fresh = render_headless(&Scene::procedural(), &camera, 2, 1.0, 160, 120);
+    render_headless(&scene, &far, 2, 1.0, 160, 120);
+    let reused = render_headless(&scene, &camera, 2, 1.0, 160, 120);
+    assert!(fresh.buffer == reused.buffer);
+}
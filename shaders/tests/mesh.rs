@@ -0,0 +1,87 @@
+mod common;
+
+use nalgebra_glm::Vec3;
+use shaders::mesh::NormalMode;
+use shaders::obj::Obj;
+use common::write_model;
+
+// Cubo de lado 2 centrado en el origen, sin normales ni coordenadas de textura
+const CUBE: &str = "\
+v -1 -1 -1
+v 1 -1 -1
+v 1 1 -1
+v -1 1 -1
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 4 8 7 3
+f 1 5 8 4
+f 2 3 7 6
+";
+
+fn assert_close(a: Vec3, b: Vec3) {
+    assert!((a - b).magnitude() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn missing_normals_are_smoothed_up_to_the_crease_angle() {
+    let path = write_model("cube", &[("cube.obj", CUBE)]);
+    let mut cube = Obj::load(&path).unwrap();
+
+    // Las caras del cubo forman 90°, más que el pliegue por defecto: cada esquina se separa en tres
+    assert_eq!(cube.meshes()[0].vertex_count(), 24);
+    for vertex in cube.get_vertex_array() {
+        let axis = vertex.position.component_mul(&vertex.normal.map(|n| n.abs()));
+        assert_close(vertex.normal, axis);
+    }
+
+    // Con un pliegue mayor el cubo queda redondeado, con normales diagonales
+    cube.compute_normals(NormalMode::Smooth { crease_angle: 100f32.to_radians() });
+    assert_eq!(cube.meshes()[0].vertex_count(), 8);
+    for vertex in cube.get_vertex_array() {
+        assert_close(vertex.normal, vertex.position.normalize());
+    }
+
+    cube.compute_normals(NormalMode::Flat);
+    assert_eq!(cube.meshes()[0].vertex_count(), 24);
+    assert_eq!(cube.meshes()[0].triangle_count(), 12);
+}
+
+#[test]
+fn tangents_follow_the_texture_coordinates() {
+    // Un cuadrado en el plano xy con u hacia +x y v hacia +y en el archivo
+    let path = write_model("quad", &[("quad.obj", "\
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3 4/4
+")]);
+    let quad = Obj::load(&path).unwrap();
+    for vertex in quad.get_vertex_array() {
+        assert_close(vertex.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_close(vertex.tangent, Vec3::new(1.0, 0.0, 0.0));
+        // Las coordenadas de textura se invierten al cargar, así que v crece hacia -y
+        assert_close(vertex.bitangent, Vec3::new(0.0, -1.0, 0.0));
+    }
+}
+
+#[test]
+fn bundled_sphere_has_an_orthonormal_tangent_basis() {
+    let sphere = Obj::load("assets/models/sphere.obj").unwrap();
+    for vertex in sphere.get_vertex_array() {
+        let normal = vertex.normal.normalize();
+        assert!((vertex.tangent.magnitude() - 1.0).abs() < 1e-3);
+        assert!((vertex.bitangent.magnitude() - 1.0).abs() < 1e-3);
+        assert!(vertex.tangent.dot(&normal).abs() < 1e-3);
+        assert!(vertex.bitangent.dot(&normal).abs() < 1e-3);
+    }
+}
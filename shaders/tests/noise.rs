@@ -0,0 +1,95 @@
+mod common;
+
+use std::f32::consts::PI;
+use nalgebra_glm::{Mat4, Vec3};
+use shaders::body::MOON;
+use shaders::noise::{create_noise, sample_sphere, sample_sphere_at};
+use shaders::scene::Scene;
+use shaders::shaders::fragment_shader;
+use shaders::Uniforms;
+use common::fragment_at;
+
+// Sin relieve, para comparar sólo el color de la superficie
+fn uniforms(shader: u8) -> Uniforms {
+    Uniforms { time: 1.0, relief: None, ..Uniforms::for_shader(shader, Mat4::identity()) }
+}
+
+#[test]
+fn sampling_depends_only_on_the_direction() {
+    let noise = create_noise(2);
+    let point = Vec3::new(0.2, -0.7, 0.4);
+    let value = sample_sphere(&noise, &point, 1.0);
+    assert_eq!(sample_sphere(&noise, &(point * 3.0), 1.0), value);
+    assert_eq!(sample_sphere(&noise, &point.normalize(), 1.0), value);
+    assert_eq!(sample_sphere_at(&noise, &point, 1.0, Vec3::zeros()), value);
+    // La escala cambia el tamaño de los detalles
+    assert_ne!(sample_sphere(&noise, &point, 2.0), value);
+}
+
+#[test]
+fn front_and_back_are_not_mirrored() {
+    let noise = create_noise(3);
+    let differs = (0..16).any(|i| {
+        let angle = i as f32 * 0.4;
+        let front = Vec3::new(angle.cos() * 0.5, angle.sin() * 0.5, 0.7);
+        let back = Vec3::new(front.x, front.y, -front.z);
+        (sample_sphere(&noise, &front, 1.0) - sample_sphere(&noise, &back, 1.0)).abs() > 1e-3
+    });
+    assert!(differs);
+}
+
+#[test]
+fn there_is_no_seam_or_pole_pinch() {
+    let noise = create_noise(1);
+    // Longitudes a ambos lados del meridiano donde se cortan las uv
+    let at = |longitude: f32, latitude: f32| {
+        let direction = Vec3::new(latitude.cos() * longitude.cos(), latitude.sin(), latitude.cos() * longitude.sin());
+        sample_sphere(&noise, &direction, 1.0)
+    };
+    for latitude in [-1.2, -0.5, 0.0, 0.5, 1.2] {
+        assert!((at(PI - 1e-5, latitude) - at(-PI + 1e-5, latitude)).abs() < 1e-3);
+    }
+    // Cerca del polo todas las longitudes ven casi el mismo punto
+    let near_pole: Vec<f32> = (0..8).map(|i| at(i as f32 * PI / 4.0, PI / 2.0 - 1e-5)).collect();
+    let spread = near_pole.iter().cloned().fold(f32::NEG_INFINITY, f32::max)
+        - near_pole.iter().cloned().fold(f32::INFINITY, f32::min);
+    assert!(spread < 1e-3, "{}", spread);
+}
+
+#[test]
+fn planet_shaders_are_not_mirrored_front_to_back() {
+    for shader in [1, 2, 3, 5, 6, 8] {
+        let uniforms = uniforms(shader);
+        let differs = (0..16).any(|i| {
+            let angle = i as f32 * 0.4;
+            let front = Vec3::new(angle.cos() * 0.5, angle.sin() * 0.5, 0.7);
+            let back = Vec3::new(front.x, front.y, -front.z);
+            // La misma normal para los dos, así la iluminación no cuenta
+            let mut back_fragment = fragment_at(back, &uniforms);
+            back_fragment.normal = front;
+            back_fragment.world_position = front;
+            fragment_shader(&fragment_at(front, &uniforms), &uniforms, 1.0).0.to_hex()
+                != fragment_shader(&back_fragment, &uniforms, 1.0).0.to_hex()
+        });
+        assert!(differs, "{}", shader);
+    }
+}
+
+#[test]
+fn the_moon_looks_the_same_from_the_mars_view_and_the_system_view() {
+    let moon = |planet: u8| {
+        Scene::objects(planet, 12.5).into_iter().find(|object| object.name == MOON.name).unwrap().uniforms()
+    };
+    let (mars_view, system_view) = (moon(2), moon(0));
+    for i in 0..16 {
+        let angle = i as f32 * 0.4;
+        let point = Vec3::new(angle.cos() * 0.6, angle.sin() * 0.6, 0.5).normalize();
+        // El mismo fragmento en las dos vistas: sólo cambian los uniforms del shader
+        let fragment = fragment_at(point, &mars_view);
+        assert_eq!(
+            fragment_shader(&fragment, &mars_view, 1.0).0.to_hex(),
+            fragment_shader(&fragment, &system_view, 1.0).0.to_hex(),
+            "{:?}", point
+        );
+    }
+}
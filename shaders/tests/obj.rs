@@ -0,0 +1,128 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use nalgebra_glm::Vec3;
+use shaders::obj::{Obj, ObjError};
+use common::write_model;
+
+const PLANET_OBJ: &str = "\
+mtllib planet.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 1
+vn 0 0 1
+o Surface
+usemtl rock
+f 1/1/1 2/2/1 3/2/1 4/1/1
+o Glow
+usemtl lava
+f -4//1 -2//1 -1//1
+";
+
+const PLANET_MTL: &str = "\
+# dos materiales
+newmtl rock
+Kd 0.5 0.4 0.3
+Ks 0.1
+Ns 32
+map_Kd -bm 1.0 textures/rock.png
+
+newmtl lava
+Ke 1 0.3 0
+d 0.5
+";
+
+#[test]
+fn keeps_meshes_and_materials_separate() {
+    let path = write_model("planet", &[("planet.obj", PLANET_OBJ), ("planet.mtl", PLANET_MTL)]);
+    let obj = Obj::load(&path).unwrap();
+
+    let meshes = obj.meshes();
+    assert_eq!(meshes.len(), 2);
+    assert_eq!((meshes[0].name.as_str(), meshes[0].material.as_deref()), ("Surface", Some("rock")));
+    assert_eq!((meshes[1].name.as_str(), meshes[1].material.as_deref()), ("Glow", Some("lava")));
+    // El cuadrado se divide en dos triángulos
+    assert_eq!(meshes[0].triangle_count(), 2);
+    assert_eq!(meshes[1].triangle_count(), 1);
+    assert_eq!(obj.get_vertex_array().len(), 9);
+
+    let rock = obj.material(&meshes[0]).unwrap();
+    assert_eq!(rock.diffuse, Vec3::new(0.5, 0.4, 0.3));
+    assert_eq!(rock.specular, Vec3::new(0.1, 0.1, 0.1));
+    assert_eq!(rock.shininess, 32.0);
+    assert_eq!(rock.diffuse_map, Some(path.parent().unwrap().join("textures/rock.png")));
+    assert!(!rock.is_emissive());
+
+    let lava = obj.material(&meshes[1]).unwrap();
+    assert_eq!(lava.emissive, Vec3::new(1.0, 0.3, 0.0));
+    assert_eq!(lava.opacity, 0.5);
+    assert!(lava.is_emissive());
+
+    // Los índices negativos cuentan desde el último vértice y sin coordenadas de textura se usa 0
+    let glow = meshes[1].get_vertex_array();
+    assert_eq!(glow[0].position, Vec3::new(0.0, 0.0, 0.0));
+    assert_eq!(glow[2].position, Vec3::new(0.0, 1.0, 0.0));
+    assert_eq!(glow[0].tex_coords.x, 0.0);
+}
+
+#[test]
+fn missing_material_library_keeps_the_geometry() {
+    let path = write_model("no-mtl", &[("planet.obj", PLANET_OBJ)]);
+    let obj = Obj::load(&path).unwrap();
+    assert_eq!(obj.meshes().len(), 2);
+    assert!(obj.materials().is_empty());
+    assert_eq!(obj.meshes()[0].material.as_deref(), Some("rock"));
+    assert!(obj.material(&obj.meshes()[0]).is_none());
+}
+
+#[test]
+fn saved_models_find_their_materials_from_another_directory() {
+    let path = write_model("save-source", &[("planet.obj", PLANET_OBJ), ("planet.mtl", PLANET_MTL)]);
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("obj").join("save-output").join("nested");
+    fs::create_dir_all(&output).unwrap();
+    let saved = output.join("planet.obj");
+    Obj::load(&path).unwrap().save(&saved).unwrap();
+
+    assert!(fs::read_to_string(&saved).unwrap().starts_with("mtllib ../../save-source/planet.mtl\n"));
+    let obj = Obj::load(&saved).unwrap();
+    assert_eq!(obj.materials().len(), 2);
+    assert_eq!(obj.material(&obj.meshes()[0]).unwrap().diffuse, Vec3::new(0.5, 0.4, 0.3));
+
+    // Junto al original el MTL se sigue nombrando igual
+    let beside = path.with_file_name("copy.obj");
+    Obj::load(&path).unwrap().save(&beside).unwrap();
+    assert!(fs::read_to_string(&beside).unwrap().starts_with("mtllib planet.mtl\n"));
+}
+
+#[test]
+fn errors_point_at_the_file_and_line() {
+    let path = write_model("bad-face", &[("bad.obj", "v 0 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 7\n")]);
+    let err = Obj::load(&path).err().unwrap();
+    assert_eq!(err.line(), Some(5));
+    assert_eq!(err.to_string(), format!("{}:5: vertex index 7 is out of range, there are 3", path.display()));
+
+    let path = write_model("bad-number", &[("bad.obj", "v 0 zero 0\n")]);
+    assert!(Obj::load(&path).err().unwrap().to_string().ends_with(":1: invalid number \"zero\""));
+
+    let path = write_model("bad-mtl", &[("bad.obj", "mtllib bad.mtl\n"), ("bad.mtl", "newmtl a\nKd 1 x 1\n")]);
+    let err = Obj::load(&path).err().unwrap();
+    assert_eq!(err.path(), path.with_extension("mtl"));
+    assert_eq!(err.line(), Some(2));
+
+    let err = Obj::load("assets/models/missing.obj").err().unwrap();
+    assert!(matches!(err, ObjError::Io { .. }));
+    assert!(err.to_string().starts_with("assets/models/missing.obj: "));
+}
+
+#[test]
+fn loads_the_bundled_models() {
+    for (file, triangles) in [("sphere.obj", 2348), ("moon.obj", 960), ("ring.obj", 64)] {
+        let obj = Obj::load(Path::new("assets/models").join(file)).unwrap();
+        assert_eq!(obj.meshes().len(), 1, "{}", file);
+        assert_eq!(obj.meshes()[0].triangle_count(), triangles, "{}", file);
+    }
+}
@@ -0,0 +1,79 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use shaders::orbit::{solve_kepler, OrbitalElements};
+
+fn elements(eccentricity: f32, inclination: f32, ascending_node: f32, argument_of_periapsis: f32) -> OrbitalElements {
+  OrbitalElements {
+    semi_major_axis: 2.0,
+    eccentricity,
+    inclination,
+    ascending_node,
+    argument_of_periapsis,
+    mean_anomaly_at_epoch: 0.0,
+    period: 100.0,
+  }
+}
+
+#[test]
+fn kepler_solution_satisfies_the_equation() {
+  for eccentricity in [0.0, 0.0167, 0.2, 0.5, 0.9, 0.95] {
+    for mean_anomaly in [0.0, 1e-4, 0.01, 0.5, 1.0, PI - 0.01, PI, 4.0, 2.0 * PI - 0.01, 2.0 * PI - 1e-4] {
+      let eccentric_anomaly = solve_kepler(mean_anomaly, eccentricity);
+      let residual = eccentric_anomaly - eccentricity * eccentric_anomaly.sin() - mean_anomaly;
+      assert!(residual.abs() < 1e-5, "e {} M {} E {} residual {}", eccentricity, mean_anomaly, eccentric_anomaly, residual);
+    }
+  }
+}
+
+#[test]
+fn perihelion_and_aphelion_radii() {
+  for eccentricity in [0.0167, 0.2056, 0.9] {
+    let orbit = elements(eccentricity, 0.3, 1.2, 0.7);
+    let a = orbit.semi_major_axis;
+    let perihelion = orbit.position(0.0).magnitude();
+    let aphelion = orbit.position(orbit.period / 2.0).magnitude();
+    assert!((perihelion - a * (1.0 - eccentricity)).abs() < 1e-4, "{} {}", eccentricity, perihelion);
+    assert!((aphelion - a * (1.0 + eccentricity)).abs() < 1e-4, "{} {}", eccentricity, aphelion);
+    // Every other point lies between the two
+    for step in 1..20 {
+      let radius = orbit.position(orbit.period * step as f32 / 20.0).magnitude();
+      assert!(radius > perihelion - 1e-4 && radius < aphelion + 1e-4, "{}", radius);
+    }
+  }
+}
+
+#[test]
+fn ecliptic_axes_map_to_the_scene() {
+  // Periapsis on ecliptic +X stays on scene +X
+  let flat = elements(0.1, 0.0, 0.0, 0.0);
+  assert!((flat.position(0.0) - Vec3::new(1.8, 0.0, 0.0)).magnitude() < 1e-5);
+  // Periapsis on ecliptic +Y lands on scene -Z
+  let turned = elements(0.1, 0.0, PI / 2.0, 0.0);
+  assert!((turned.position(0.0) - Vec3::new(0.0, 0.0, -1.8)).magnitude() < 1e-5);
+  // Prograde orbits run counterclockwise seen from scene +Y: from +X towards -Z
+  assert!(flat.position(flat.period / 4.0).z < 0.0);
+}
+
+#[test]
+fn inclined_orbits_rise_north_after_the_ascending_node() {
+  // Periapsis 90° past the node is the northernmost point of the orbit
+  let inclination = 30.0 * PI / 180.0;
+  let orbit = elements(0.0, inclination, 0.0, PI / 2.0);
+  let highest = orbit.position(0.0);
+  assert!((highest.y - 2.0 * inclination.sin()).abs() < 1e-5, "{:?}", highest);
+  let lowest = orbit.position(orbit.period / 2.0);
+  assert!((lowest.y + 2.0 * inclination.sin()).abs() < 1e-5, "{:?}", lowest);
+
+  // Right after crossing the node on ecliptic +X the body is above the ecliptic
+  let crossing = elements(0.0, inclination, 0.0, 0.0);
+  assert!(crossing.position(0.0).y.abs() < 1e-5);
+  assert!(crossing.position(1.0).y > 0.0);
+  assert!(crossing.position(crossing.period - 1.0).y < 0.0);
+
+  // Real planets stay within a few degrees of the ecliptic
+  for shader in [1, 2, 3, 4, 5, 6] {
+    let planet = OrbitalElements::for_shader(shader).unwrap();
+    let position = planet.position(1234.0);
+    assert!(position.y.abs() / position.magnitude() < (8.0 * PI / 180.0).sin(), "{}", shader);
+  }
+}
@@ -0,0 +1,56 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::render::screen_ray;
+use shaders::scene::Scene;
+
+const WIDTH: f32 = 680.0;
+const HEIGHT: f32 = 800.0;
+
+#[test]
+fn ray_through_the_middle_of_the_screen_follows_the_view() {
+    let camera = Camera::default();
+    let (origin, direction) = screen_ray(&camera, WIDTH / 2.0, HEIGHT / 2.0, WIDTH, HEIGHT);
+    assert!((direction - camera.forward()).magnitude() < 1e-4);
+    assert!((origin - camera.eye).cross(&camera.forward()).magnitude() < 1e-4);
+}
+
+#[test]
+fn orthographic_rays_are_parallel() {
+    let mut camera = Camera::default();
+    camera.toggle_projection();
+
+    let (origin_a, direction_a) = screen_ray(&camera, 0.0, 0.0, WIDTH, HEIGHT);
+    let (origin_b, direction_b) = screen_ray(&camera, WIDTH, HEIGHT, WIDTH, HEIGHT);
+    assert!((direction_a - camera.forward()).magnitude() < 1e-4);
+    assert!((direction_b - camera.forward()).magnitude() < 1e-4);
+    assert!((origin_a - origin_b).magnitude() > 1.0);
+}
+
+#[test]
+fn picking_finds_the_body_under_the_cursor() {
+    let scene = Scene::load().unwrap();
+    let camera = Camera::default();
+
+    let (origin, direction) = screen_ray(&camera, WIDTH / 2.0, HEIGHT / 2.0, WIDTH, HEIGHT);
+    let body = scene.pick(1, 0.0, origin, direction).expect("the planet is in the middle of the view");
+    assert_eq!(body.shader, 1);
+    assert_eq!(body.position, Vec3::zeros());
+
+    let (origin, corner) = screen_ray(&camera, 0.0, 0.0, WIDTH, HEIGHT);
+    assert_eq!(scene.pick(1, 0.0, origin, corner), None);
+}
+
+#[test]
+fn picking_in_system_mode_returns_the_nearest_body() {
+    let scene = Scene::load().unwrap();
+    let time = 12.5;
+
+    for body in scene.bodies(0, time) {
+        // Mirando al cuerpo desde fuera de su esfera, el rayo central debe encontrarlo primero
+        let eye = body.position + Vec3::new(0.0, body.radius * 1.5, body.radius * 3.0);
+        let camera = Camera::new(eye, body.position, Vec3::new(0.0, 1.0, 0.0));
+        let (origin, direction) = screen_ray(&camera, WIDTH / 2.0, HEIGHT / 2.0, WIDTH, HEIGHT);
+        let picked = scene.pick(0, time, origin, direction).unwrap();
+        assert_eq!(picked.shader, body.shader);
+    }
+}
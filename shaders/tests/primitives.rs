@@ -0,0 +1,115 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::obj::Mesh;
+use shaders::primitives::{annulus, cube_sphere, icosphere, uv_sphere, SphereGenerator};
+use shaders::scene::{render_headless, Scene, BACKGROUND_COLOR};
+
+// Todos los triángulos miran hacia afuera y no hay triángulos degenerados
+fn assert_outward(mesh: &Mesh, center_of: impl Fn(Vec3) -> Vec3) {
+    let vertices = mesh.get_vertex_array();
+    for triangle in vertices.chunks(3) {
+        let (a, b, c) = (triangle[0].position, triangle[1].position, triangle[2].position);
+        let normal = (b - a).cross(&(c - a));
+        assert!(normal.magnitude() > 1e-7, "{}: degenerate triangle", mesh.name);
+        assert!(normal.dot(&center_of((a + b + c) / 3.0)) > 0.0, "{}: inward triangle", mesh.name);
+    }
+}
+
+fn assert_unit_sphere(mesh: &Mesh) {
+    for vertex in mesh.get_vertex_array() {
+        assert!((vertex.position.magnitude() - 1.0).abs() < 1e-5, "{}", mesh.name);
+        assert!((vertex.normal - vertex.position).magnitude() < 1e-5, "{}", mesh.name);
+        assert!(vertex.tangent.dot(&vertex.normal).abs() < 1e-3, "{}", mesh.name);
+        assert!((0.0..=1.0).contains(&vertex.tex_coords.y), "{}", mesh.name);
+    }
+    assert_outward(mesh, |center| center);
+}
+
+#[test]
+fn spheres_have_the_requested_resolution() {
+    let sphere = uv_sphere(16, 8);
+    // Dos triángulos por cuadro salvo en las filas de los polos
+    assert_eq!(sphere.triangle_count(), 16 * 8 * 2 - 2 * 16);
+    assert_eq!(sphere.vertex_count(), 17 * 9);
+    assert_unit_sphere(&sphere);
+
+    for subdivisions in 0..4 {
+        let sphere = icosphere(subdivisions);
+        assert_eq!(sphere.triangle_count(), 20 * 4usize.pow(subdivisions));
+        assert_unit_sphere(&sphere);
+    }
+
+    let sphere = cube_sphere(6);
+    assert_eq!(sphere.triangle_count(), 6 * 6 * 6 * 2);
+    assert_unit_sphere(&sphere);
+}
+
+#[test]
+fn texture_seam_does_not_wrap_across_triangles() {
+    // Ningún triángulo debe recorrer toda la textura de vuelta en u
+    for sphere in [uv_sphere(24, 12), icosphere(3), cube_sphere(8)] {
+        for triangle in sphere.get_vertex_array().chunks(3) {
+            let u = triangle.iter().map(|vertex| vertex.tex_coords.x);
+            let (min, max) = u.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), u| (min.min(u), max.max(u)));
+            assert!(max - min < 0.5, "{}", sphere.name);
+        }
+    }
+}
+
+#[test]
+fn annulus_is_a_flat_ring_facing_up() {
+    let ring = annulus(0.6, 1.0, 32);
+    assert_eq!(ring.triangle_count(), 64);
+    for vertex in ring.get_vertex_array() {
+        let radius = vertex.position.magnitude();
+        assert_eq!(vertex.position.y, 0.0);
+        assert_eq!(vertex.normal, Vec3::y());
+        assert!((radius - 0.6).abs() < 1e-5 || (radius - 1.0).abs() < 1e-5);
+        assert_eq!(vertex.tex_coords.y, if radius < 0.8 { 0.0 } else { 1.0 });
+    }
+    assert_outward(&ring, |_| Vec3::y());
+
+    // Sin radio interior queda un disco de un triángulo por segmento
+    assert_eq!(annulus(0.0, 1.0, 32).triangle_count(), 32);
+}
+
+#[test]
+fn procedural_scene_covers_the_same_pixels_as_the_models() {
+    let camera = Camera::default();
+    let covered = |scene: &Scene, planet: u8| {
+        let framebuffer = render_headless(scene, &camera, planet, 2.0, 85, 100);
+        framebuffer.buffer.iter().filter(|&&pixel| pixel != BACKGROUND_COLOR).count() as f32
+    };
+
+    let procedural = Scene::procedural();
+    let loaded = Scene::load().unwrap();
+    for planet in [1, 2] {
+        let (generated, reference) = (covered(&procedural, planet), covered(&loaded, planet));
+        assert!((generated - reference).abs() / reference < 0.05, "planet {}: {} vs {}", planet, generated, reference);
+    }
+    // Los anillos generados tienen un hueco que deja ver el fondo entre ellos y el planeta
+    assert!(covered(&procedural, 4) < covered(&loaded, 4));
+}
+
+#[test]
+fn procedural_scene_picks_the_sphere_of_each_body() {
+    let scene = Scene::procedural_with(|shader| match shader {
+        2 => SphereGenerator::CubeSphere(8),
+        8 => SphereGenerator::Uv { segments: 24, rings: 12 },
+        _ => SphereGenerator::Icosphere(3),
+    });
+    let finest = |shader: u8| scene.body_mesh(shader).finest().triangle_count();
+    assert_eq!(finest(2), cube_sphere(8).triangle_count());
+    assert_eq!(finest(8), uv_sphere(24, 12).triangle_count());
+    assert_eq!(finest(1), icosphere(3).triangle_count());
+    assert_eq!(scene.body_mesh(8).radius(), 0.5);
+
+    // Cada cuerpo sigue ocupando lo mismo en pantalla que con la icoesfera
+    let camera = Camera::default();
+    let covered = |scene: &Scene| {
+        let framebuffer = render_headless(scene, &camera, 2, 2.0, 85, 100);
+        framebuffer.buffer.iter().filter(|&&pixel| pixel != BACKGROUND_COLOR).count() as f32
+    };
+    let (generated, reference) = (covered(&scene), covered(&Scene::procedural()));
+    assert!((generated - reference).abs() / reference < 0.05, "{} vs {}", generated, reference);
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::Path;
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::framebuffer::Framebuffer;
+use shaders::profiler::{FrameProfile, Profiler};
+use shaders::scene::{LodState, Scene, BACKGROUND_COLOR};
+
+fn render_profiled(scene: &Scene, camera: &Camera, planet: u8) -> FrameProfile {
+    let mut framebuffer = Framebuffer::new(68, 80);
+    framebuffer.set_background_color(BACKGROUND_COLOR);
+    scene.render(&mut framebuffer, camera, planet, 2.0, &mut LodState::new())
+}
+
+#[test]
+fn every_fragment_is_shaded_rejected_or_clipped() {
+    let scene = Scene::load().expect("Failed to load obj");
+    let profile = render_profiled(&scene, &Camera::default(), 4);
+
+    let names: Vec<&str> = profile.objects.iter().map(|object| object.name.as_str()).collect();
+    assert_eq!(names, ["Saturn", "Saturn rings"]);
+
+    for object in &profile.objects {
+        let stats = &object.stats;
+        assert!(stats.triangles > 0);
+        assert_eq!(stats.fragments, stats.shaded + stats.depth_rejected + stats.clipped, "{}", object.name);
+    }
+    // La mitad de atrás de la esfera queda tapada por la de adelante
+    assert!(profile.geometry().depth_rejected > 0);
+}
+
+#[test]
+fn sun_glow_is_timed_as_separate_stages() {
+    let scene = Scene::load().expect("Failed to load obj");
+    let profile = render_profiled(&scene, &Camera::default(), 7);
+    let stages: Vec<&str> = profile.stages.iter().map(|stage| stage.name).collect();
+    assert_eq!(stages, ["blur", "bloom"]);
+
+    let profile = render_profiled(&scene, &Camera::default(), 1);
+    assert!(profile.stages.is_empty());
+}
+
+#[test]
+fn writes_csv_and_chrome_trace() {
+    let scene = Scene::load().expect("Failed to load obj");
+    let camera = Camera::new(Vec3::new(0.0, 12.0, 18.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let mut profiler = Profiler::new();
+    for _ in 0..2 {
+        profiler.record(render_profiled(&scene, &camera, 0));
+    }
+
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let csv = directory.join("profile.csv");
+    profiler.write_csv(&csv).unwrap();
+    let csv = fs::read_to_string(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("frame,total_ms,vertex_ms,raster_ms,fragment_ms,blur_ms"));
+    assert!(lines[0].ends_with(",clipped,vertices"));
+    assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+    // Sol, seis planetas, la luna de Marte y los anillos de Saturno
+    assert_eq!(lines[1].split(',').nth(9), Some("9"));
+
+    let trace = directory.join("profile.json");
+    profiler.write_chrome_trace(&trace).unwrap();
+    let trace = fs::read_to_string(trace).unwrap();
+    assert!(trace.trim_start().starts_with('[') && trace.trim_end().ends_with(']'));
+    assert_eq!(trace.matches(r#""cat":"frame""#).count(), 2);
+    assert_eq!(trace.matches(r#""name":"Saturn rings","cat":"object""#).count(), 2);
+    assert!(trace.contains(r#""name":"bloom","cat":"stage""#));
+}
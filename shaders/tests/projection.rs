@@ -0,0 +1,52 @@
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+use shaders::camera::Camera;
+use shaders::render::{create_projection_matrix, create_view_matrix};
+use shaders::scene::{render_headless, Scene, BACKGROUND_COLOR};
+
+fn project(camera: &Camera, point: Vec3, near: f32, far: f32) -> Vec3 {
+    let clip = create_projection_matrix(camera, 680.0, 800.0, near, far)
+        * create_view_matrix(camera.eye, camera.center, camera.up)
+        * Vec4::new(point.x, point.y, point.z, 1.0);
+    Vec3::new(clip.x, clip.y, clip.z) / clip.w
+}
+
+#[test]
+fn clip_range_encloses_every_body_in_view() {
+    let scene = Scene::load().unwrap();
+    let camera = Camera::new(Vec3::new(0.0, 12.0, 18.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+    let (near, far) = scene.clip_range(&camera, 0, 12.5);
+    assert!(near > 0.1 && far < 1000.0, "near {} far {}", near, far);
+
+    for body in scene.bodies(0, 12.5) {
+        let depth = (body.position - camera.eye).dot(&camera.forward());
+        if depth > 0.0 {
+            assert!(depth - body.radius >= near - 1e-3 && depth + body.radius <= far);
+        }
+    }
+}
+
+#[test]
+fn lens_shift_moves_the_image_without_turning_the_camera() {
+    let mut camera = Camera::default();
+    assert!(project(&camera, camera.center, 0.1, 100.0).xy().magnitude() < 1e-5);
+
+    camera.lens_shift = Vec2::new(0.25, -0.1);
+    let shifted = project(&camera, camera.center, 0.1, 100.0);
+    assert!((shifted.xy() - camera.lens_shift).magnitude() < 1e-5);
+
+    camera.toggle_projection();
+    let shifted = project(&camera, camera.center, 0.1, 100.0);
+    assert!((shifted.xy() - camera.lens_shift).magnitude() < 1e-5);
+}
+
+#[test]
+fn bodies_skipped_by_the_clip_range_are_not_drawn() {
+    let scene = Scene::load().unwrap();
+    // Por encima del plano de las órbitas y mirando hacia arriba: todo el sistema queda detrás
+    let camera = Camera::new(Vec3::new(0.0, 200.0, 0.0), Vec3::new(0.0, 400.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+    let forward = camera.forward();
+    assert!(scene.bodies(0, 12.5).iter().all(|body| (body.position - camera.eye).dot(&forward) + body.radius <= 0.0));
+
+    let framebuffer = render_headless(&scene, &camera, 0, 12.5, 170, 200);
+    assert!(framebuffer.buffer.iter().all(|&color| color == BACKGROUND_COLOR));
+}
@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::Path;
+use shaders::camera::Camera;
+use shaders::record::{record, RecordFormat, RecordSettings};
+use shaders::scene::Scene;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn parses_record_arguments() {
+    let settings = RecordSettings::from_args(&args("shaders --record y4m --planet 5 --duration 2 --fps 12 --size 64x48 --out a.y4m"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.format, RecordFormat::Y4m);
+    assert_eq!(settings.planet, 5);
+    assert_eq!((settings.width, settings.height), (64, 48));
+    assert_eq!(settings.frame_count(), 24);
+    assert_eq!(settings.output, Path::new("a.y4m"));
+
+    assert!(RecordSettings::from_args(&args("shaders")).unwrap().is_none());
+    assert!(RecordSettings::from_args(&args("shaders --record mp4")).is_err());
+}
+
+#[test]
+fn writes_uncompressed_y4m_stream() {
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join("record.y4m");
+    let mut settings = RecordSettings::new(RecordFormat::Y4m);
+    settings.output = output.clone();
+    settings.duration = 0.5;
+    settings.fps = 6;
+    settings.width = 34;
+    settings.height = 40;
+
+    let scene = Scene::load().expect("Failed to load obj");
+    let frames = record(&scene, &Camera::default(), &settings).unwrap();
+    assert_eq!(frames, 3);
+
+    let header = "YUV4MPEG2 W34 H40 F6:1 Ip A1:1 C444\n";
+    let bytes = fs::read(&output).unwrap();
+    assert!(bytes.starts_with(header.as_bytes()));
+    assert_eq!(bytes.len(), header.len() + 3 * ("FRAME\n".len() + 34 * 40 * 3));
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use nalgebra_glm::{mat4_to_mat3, Vec3};
+use shaders::noise::create_noise;
+use shaders::primitives::icosphere;
+use shaders::relief::Relief;
+use shaders::render::create_model_matrix;
+use shaders::shaders::{fragment_shader, shading_normal};
+use shaders::Uniforms;
+use common::{camera_uniforms, fragment_at};
+
+fn uniforms(shader: u8, scale: f32, rotation: Vec3) -> Uniforms {
+    camera_uniforms(shader, create_model_matrix(Vec3::zeros(), scale, rotation), 100.0, 100.0)
+}
+
+fn sample_points() -> Vec<Vec3> {
+    (0..32).map(|i| {
+        let angle = i as f32 * 0.37;
+        Vec3::new(angle.cos(), (i as f32 * 0.11).sin(), angle.sin()).normalize()
+    }).collect()
+}
+
+#[test]
+fn only_rocky_bodies_have_relief() {
+    for shader in [2, 3, 8] {
+        assert!(Relief::for_shader(shader).is_some(), "{}", shader);
+    }
+    for shader in [1, 4, 5, 6, 7, 9] {
+        assert!(Relief::for_shader(shader).is_none(), "{}", shader);
+    }
+}
+
+#[test]
+fn heights_scale_with_the_radius() {
+    let relief = Relief::for_shader(2).unwrap();
+    let point = Vec3::new(0.3, 0.8, -0.52).normalize();
+    let height = relief.height(&point);
+    assert!(height.abs() <= relief.amplitude());
+    // La luna usa la misma forma a la mitad del tamaño
+    assert!((relief.height(&(point * 0.5)) - height * 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn displacement_moves_the_silhouette() {
+    let relief = Relief::for_shader(2).unwrap();
+    let sphere = icosphere(4).get_indexed_mesh();
+    let radii: Vec<f32> = sphere.vertices.iter().map(|vertex| relief.displace(vertex).position.magnitude()).collect();
+    let (min, max) = radii.iter().fold((f32::INFINITY, 0.0f32), |(min, max), &r| (min.min(r), max.max(r)));
+    assert!(max - min > relief.amplitude() * 0.5, "{} {}", min, max);
+    assert!(max <= 1.0 + relief.amplitude() + 1e-4 && min >= 1.0 - relief.amplitude() - 1e-4);
+}
+
+#[test]
+fn normals_follow_the_displaced_surface() {
+    let relief = Relief::for_shader(2).unwrap();
+    let sphere = icosphere(5).get_indexed_mesh();
+    let displaced: Vec<_> = sphere.vertices.iter().map(|vertex| relief.displace(vertex)).collect();
+
+    // La base tangente sigue siendo ortonormal
+    for vertex in &displaced {
+        assert!((vertex.normal.magnitude() - 1.0).abs() < 1e-4);
+        assert!(vertex.normal.dot(&vertex.tangent).abs() < 1e-4);
+        assert!(vertex.normal.dot(&vertex.bitangent).abs() < 1e-4);
+    }
+
+    // Las normales se parecen más a las de las caras desplazadas que las normales de la esfera
+    let (mut displaced_error, mut original_error) = (0.0, 0.0);
+    for triangle in sphere.indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| &displaced[triangle[k] as usize]);
+        let face = (b.position - a.position).cross(&(c.position - a.position)).normalize();
+        for k in 0..3 {
+            displaced_error += 1.0 - face.dot(&displaced[triangle[k] as usize].normal);
+            original_error += 1.0 - face.dot(&sphere.vertices[triangle[k] as usize].normal);
+        }
+    }
+    assert!(displaced_error < original_error * 0.8, "{} {}", displaced_error, original_error);
+}
+
+#[test]
+fn bump_mapping_tilts_the_lighting_normal() {
+    let smooth = Uniforms { relief: None, ..uniforms(8, 1.0, Vec3::zeros()) };
+    let bumped = uniforms(8, 1.0, Vec3::zeros());
+
+    let mut changed = 0;
+    for point in sample_points() {
+        let fragment = fragment_at(point, &bumped);
+        assert_eq!(shading_normal(&fragment, &smooth), fragment.normal);
+        let normal = shading_normal(&fragment, &bumped);
+        assert!((normal.magnitude() - 1.0).abs() < 1e-4);
+        if normal.dot(&fragment.normal) < 0.9999 {
+            changed += 1;
+        }
+        // El detalle sólo inclina la normal dentro del plano tangente
+        let slope = bumped.relief.as_ref().unwrap().detail_slope(&point);
+        assert!(slope.dot(&point).abs() < 1e-4);
+    }
+    assert!(changed > 16, "{}", changed);
+
+    // Sin fuerza de bump la normal queda como la interpolada
+    let flat = Relief::new(create_noise(8), 0.04).with_bump(0.0);
+    assert_eq!(flat.detail_slope(&Vec3::new(0.0, 0.0, 1.0)), Vec3::zeros());
+}
+
+#[test]
+fn bump_mapping_follows_the_model_rotation_and_scale() {
+    let rotation = Vec3::new(0.3, 1.2, 0.0);
+    let still = uniforms(2, 1.0, Vec3::zeros());
+    let turned = uniforms(2, 2.5, rotation);
+    let model = mat4_to_mat3(&create_model_matrix(Vec3::zeros(), 1.0, rotation));
+    for point in sample_points() {
+        let expected = model * shading_normal(&fragment_at(point, &still), &still);
+        let normal = shading_normal(&fragment_at(point, &turned), &turned);
+        assert!((normal - expected).magnitude() < 1e-3, "{:?} {:?}", normal, expected);
+    }
+}
+
+#[test]
+fn rocky_shaders_light_through_the_bumped_normal() {
+    for shader in [2, 3, 8] {
+        let bumped = uniforms(shader, 1.0, Vec3::zeros());
+        let smooth = Uniforms { relief: None, ..uniforms(shader, 1.0, Vec3::zeros()) };
+        let differs = sample_points().into_iter().any(|point| {
+            let fragment = fragment_at(point, &bumped);
+            fragment_shader(&fragment, &bumped, 1.0).0.to_hex() != fragment_shader(&fragment, &smooth, 1.0).0.to_hex()
+        });
+        assert!(differs, "{}", shader);
+    }
+}
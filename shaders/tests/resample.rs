@@ -0,0 +1,37 @@
+use shaders::framebuffer::Framebuffer;
+use shaders::postprocess::{resample, resample_bilinear};
+
+#[test]
+fn downscaling_averages_covered_pixels() {
+    let buffer = vec![0x000000, 0xFF0000, 0x00FF00, 0x0000FF, 0x204060, 0x204060, 0x204060, 0x204060];
+    let output = resample(&buffer, 4, 2, 2, 1);
+    assert_eq!(output, vec![0x4F2030, 0x105F6F]);
+}
+
+#[test]
+fn upscaling_repeats_the_nearest_pixel() {
+    let buffer = vec![0x112233, 0x445566];
+    let output = resample(&buffer, 2, 1, 4, 2);
+    assert_eq!(output, vec![0x112233, 0x112233, 0x445566, 0x445566, 0x112233, 0x112233, 0x445566, 0x445566]);
+    assert_eq!(resample(&buffer, 2, 1, 2, 1), buffer);
+}
+
+#[test]
+fn resized_framebuffer_matches_the_new_size() {
+    let mut framebuffer = Framebuffer::new(4, 4);
+    framebuffer.set_background_color(0x333355);
+    framebuffer.resize(6, 3);
+    framebuffer.clear();
+    assert_eq!((framebuffer.width, framebuffer.height), (6, 3));
+    assert_eq!(framebuffer.buffer, vec![0x333355; 18]);
+    assert_eq!(framebuffer.zbuffer.len(), 18);
+    assert_eq!(framebuffer.emissive_buffer.len(), 18);
+}
+
+#[test]
+fn bilinear_upscaling_blends_neighbours() {
+    let buffer = vec![0x000000, 0xFF8040];
+    let output = resample_bilinear(&buffer, 2, 1, 4, 1);
+    // Los extremos conservan los colores de origen y los centros se mezclan 3:1
+    assert_eq!(output, vec![0x000000, 0x402010, 0xBF6030, 0xFF8040]);
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+use shaders::resolution::DynamicResolution;
+
+fn run(resolution: &mut DynamicResolution, frames: usize, cost: impl Fn(f32) -> Duration) {
+    for _ in 0..frames {
+        let scale = resolution.scale();
+        resolution.update(cost(scale));
+    }
+}
+
+// Un cuadro cuesta proporcionalmente a la cantidad de píxeles
+fn pixel_cost(full_frame_ms: f32) -> impl Fn(f32) -> Duration {
+    move |scale| Duration::from_secs_f32(full_frame_ms / 1000.0 * scale * scale)
+}
+
+#[test]
+fn slow_frames_lower_the_scale_until_the_target_is_met() {
+    let mut resolution = DynamicResolution::new(30.0, 0.25, 1.0);
+    run(&mut resolution, 400, pixel_cost(66.0));
+
+    // 66 ms a escala completa: ~0.67 da 30 fps
+    let scale = resolution.scale();
+    assert!((0.6..=0.75).contains(&scale), "scale {}", scale);
+    let frame_time = pixel_cost(66.0)(scale).as_secs_f32();
+    assert!((frame_time - 1.0 / 30.0).abs() < 0.1 / 30.0 + 0.005, "frame time {}", frame_time);
+}
+
+#[test]
+fn scale_stays_within_bounds() {
+    let mut resolution = DynamicResolution::new(60.0, 0.5, 1.0);
+    run(&mut resolution, 400, pixel_cost(500.0));
+    assert_eq!(resolution.scale(), 0.5);
+
+    run(&mut resolution, 400, pixel_cost(1.0));
+    assert_eq!(resolution.scale(), 1.0);
+}
+
+#[test]
+fn manual_scale_sticks_while_disabled() {
+    let mut resolution = DynamicResolution::new(60.0, 0.5, 1.0);
+    resolution.set_enabled(false);
+    resolution.set_scale(2.0);
+    run(&mut resolution, 100, pixel_cost(500.0));
+    assert_eq!(resolution.scale(), 2.0);
+
+    resolution.set_enabled(true);
+    assert_eq!(resolution.scale(), 1.0);
+}
@@ -0,0 +1,52 @@
+use std::fs::File;
+use std::path::Path;
+use shaders::camera::Camera;
+use shaders::noise::{create_cloud_noise, create_noise};
+use shaders::scene::{render_headless, Scene};
+use shaders::screenshot::capture;
+
+#[test]
+fn captures_frame_buffers_with_metadata() {
+    let scene = Scene::load().expect("Failed to load obj");
+    let camera = Camera::default();
+    let framebuffer = render_headless(&scene, &camera, 7, 3.0, 34, 40);
+
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("screenshots");
+    let paths = capture(&directory, &framebuffer, &camera, 7, 3.0, true).unwrap();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[0].file_name().unwrap().to_str().unwrap().starts_with("sun-20"));
+    assert!(paths[1].to_str().unwrap().ends_with("-depth.png"));
+    assert!(paths[2].to_str().unwrap().ends_with("-emissive.png"));
+
+    let reader = png::Decoder::new(File::open(&paths[0]).unwrap()).read_info().unwrap();
+    let text = &reader.info().uncompressed_latin1_text;
+    let value = |key: &str| text.iter().find(|chunk| chunk.keyword == key).map(|chunk| chunk.text.clone());
+    assert_eq!(value("Planet").as_deref(), Some("7"));
+    assert_eq!(value("CameraEye").as_deref(), Some("0 2 10"));
+    assert_eq!(value("SimulationTime").as_deref(), Some("3"));
+    // El Sol no lee ruido
+    assert_eq!(value("NoiseSeeds").as_deref(), Some(""));
+}
+
+#[test]
+fn lists_the_noise_seeds_of_the_bodies_drawn() {
+    let scene = Scene::procedural();
+    let camera = Camera::default();
+    let seeds = |planet: u8| {
+        let framebuffer = render_headless(&scene, &camera, planet, 3.0, 17, 20);
+        let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("screenshot-seeds").join(planet.to_string());
+        let paths = capture(&directory, &framebuffer, &camera, planet, 3.0, false).unwrap();
+        let reader = png::Decoder::new(File::open(&paths[0]).unwrap()).read_info().unwrap();
+        let text = &reader.info().uncompressed_latin1_text;
+        text.iter().find(|chunk| chunk.keyword == "NoiseSeeds").unwrap().text.clone()
+    };
+
+    let (mars, moon) = (create_noise(2).seed, create_noise(8).seed);
+    assert_eq!(seeds(2), format!("Mars={} Moon={}", mars, moon));
+    assert_eq!(seeds(4), "");
+    assert_eq!(seeds(1), format!("Earth={} cloud={}", create_noise(1).seed, create_cloud_noise().seed));
+
+    let system = seeds(0);
+    assert!(system.contains(&format!("Moon={}", moon)) && system.contains("band="), "{}", system);
+    assert!(!system.contains("Sun=") && !system.contains("Saturn="), "{}", system);
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use shaders::obj::{Mesh, Obj};
+use shaders::primitives::{annulus, icosphere};
+use common::write_model;
+
+// Cuántas caras tiene cada arista, por posición
+fn edge_faces(mesh: &Mesh) -> HashMap<[[u32; 3]; 2], usize> {
+    let vertices = mesh.get_vertex_array();
+    let key = |index: usize| {
+        let p = vertices[index].position;
+        [(p.x + 0.0).to_bits(), (p.y + 0.0).to_bits(), (p.z + 0.0).to_bits()]
+    };
+    let mut edges = HashMap::new();
+    for triangle in 0..vertices.len() / 3 {
+        for k in 0..3 {
+            let (a, b) = (key(triangle * 3 + k), key(triangle * 3 + (k + 1) % 3));
+            *edges.entry(if a < b { [a, b] } else { [b, a] }).or_insert(0) += 1;
+        }
+    }
+    edges
+}
+
+fn area(mesh: &Mesh) -> f32 {
+    mesh.get_vertex_array().chunks_exact(3)
+        .map(|t| (t[1].position - t[0].position).cross(&(t[2].position - t[0].position)).magnitude() / 2.0)
+        .sum()
+}
+
+// OBJ con una icoesfera por cada número de subdivisiones, cada una en su propio objeto
+fn write_spheres(name: &str, subdivisions: &[u32]) -> PathBuf {
+    let mut out = String::new();
+    let mut offset = 1;
+    for (k, &level) in subdivisions.iter().enumerate() {
+        let mesh = icosphere(level).get_indexed_mesh();
+        writeln!(out, "o sphere{}", k).unwrap();
+        for vertex in &mesh.vertices {
+            let (p, n, t) = (vertex.position, vertex.normal, vertex.tex_coords);
+            writeln!(out, "v {} {} {}\nvn {} {} {}\nvt {} {}", p.x + k as f32 * 3.0, p.y, p.z, n.x, n.y, n.z, t.x, 1.0 - t.y).unwrap();
+        }
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize + offset);
+            writeln!(out, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}").unwrap();
+        }
+        offset += mesh.vertices.len();
+    }
+    write_model(name, &[(&format!("{}.obj", name), &out)])
+}
+
+#[test]
+fn reaches_the_target_and_stays_closed() {
+    let sphere = icosphere(3);
+    let simplified = sphere.simplify(320);
+    assert_eq!(simplified.triangle_count(), 320);
+    // Cada arista sigue entre dos caras: no se abren agujeros
+    assert!(edge_faces(&simplified).values().all(|&faces| faces == 2));
+    assert!(area(&simplified) > area(&sphere) * 0.9);
+}
+
+#[test]
+fn keeps_texture_seams_and_normals() {
+    let sphere = icosphere(3);
+    let original = sphere.get_vertex_array();
+    let simplified = sphere.simplify(200).get_vertex_array();
+
+    // Los vértices que quedan son del original con su misma normal y coordenada de textura
+    for vertex in &simplified {
+        assert!(original.iter().any(|o| {
+            o.position == vertex.position && o.normal == vertex.normal && o.tex_coords == vertex.tex_coords
+        }));
+    }
+    // Ningún triángulo cruza la costura estirando la textura de un lado al otro
+    for triangle in simplified.chunks_exact(3) {
+        let us = triangle.iter().map(|vertex| vertex.tex_coords.x);
+        let (min, max) = us.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), u| (min.min(u), max.max(u)));
+        assert!(max - min < 0.5, "{} {}", min, max);
+    }
+}
+
+#[test]
+fn open_borders_do_not_shrink() {
+    let ring = annulus(0.5, 1.0, 64);
+    let simplified = ring.simplify(64);
+    assert!(simplified.triangle_count() <= 64);
+    // Ninguna arista termina compartida por más de dos caras
+    assert!(edge_faces(&simplified).values().all(|&faces| faces <= 2));
+    assert!(area(&simplified) > area(&ring) * 0.9);
+}
+
+#[test]
+fn simplified_models_round_trip_through_obj() {
+    let obj = Obj::load("assets/models/sphere.obj").unwrap();
+    let simplified = obj.simplify(400);
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("sphere_400.obj");
+    simplified.save(&path).unwrap();
+
+    let loaded = Obj::load(&path).unwrap();
+    assert_eq!(loaded.meshes().len(), 1);
+    assert_eq!(loaded.meshes()[0].triangle_count(), 400);
+    assert_eq!(loaded.meshes()[0].material, obj.meshes()[0].material);
+    let (before, after) = (simplified.get_vertex_array(), loaded.get_vertex_array());
+    for (a, b) in before.iter().zip(&after) {
+        assert!((a.position - b.position).magnitude() < 1e-5);
+        assert!((a.tex_coords - b.tex_coords).magnitude() < 1e-5);
+    }
+}
+
+#[test]
+fn shares_between_meshes_never_exceed_the_target() {
+    let obj = Obj::load(write_spheres("spheres", &[3, 2, 1])).unwrap();
+    let counts: Vec<usize> = obj.meshes().iter().map(|mesh| mesh.triangle_count()).collect();
+    assert_eq!(counts, [1280, 320, 80]);
+
+    // Redondeando cada parte hacia arriba, 280 daría 214 + 54 + 14 = 282. Cada colapso en un mesh
+    // cerrado quita dos triángulos, así que cada mesh puede quedar uno por debajo de su parte
+    for target in [1001, 500, 280] {
+        let simplified = obj.simplify(target);
+        assert_eq!(simplified.meshes().len(), 3);
+        let total: usize = simplified.meshes().iter().map(|mesh| mesh.triangle_count()).sum();
+        assert!(total <= target && total + 3 >= target, "{} {}", target, total);
+        for (mesh, count) in simplified.meshes().iter().zip(&counts) {
+            assert!(mesh.triangle_count() <= *count);
+        }
+    }
+    // Pedir más de los que hay deja el modelo como estaba
+    let unchanged = obj.simplify(5000);
+    let total: usize = unchanged.meshes().iter().map(|mesh| mesh.triangle_count()).sum();
+    assert_eq!(total, 1680);
+}
@@ -0,0 +1,67 @@
+use nalgebra_glm::Vec3;
+use shaders::camera::Camera;
+use shaders::framebuffer::Framebuffer;
+use shaders::hud::{draw_hud, hud_text, FrameTimes, HudInfo};
+use shaders::profiler::RenderStats;
+use shaders::text::{draw_text, text_height, text_width, ADVANCE, GLYPH_HEIGHT, LINE_HEIGHT};
+
+fn lit_pixels(framebuffer: &Framebuffer, color: u32) -> Vec<(usize, usize)> {
+    (0..framebuffer.height)
+        .flat_map(|y| (0..framebuffer.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| framebuffer.buffer[y * framebuffer.width + x] == color)
+        .collect()
+}
+
+#[test]
+fn glyphs_are_drawn_from_the_embedded_font() {
+    let mut framebuffer = Framebuffer::new(20, 12);
+    draw_text(&mut framebuffer, 1, 2, "I", 0xFFFFFF, 1);
+
+    // La I es una columna de 7 píxeles con remates arriba y abajo
+    let pixels = lit_pixels(&framebuffer, 0xFFFFFF);
+    assert_eq!(pixels.len(), 11);
+    assert!(pixels.iter().all(|&(x, y)| (2..=4).contains(&x) && (2..=8).contains(&y)));
+}
+
+#[test]
+fn text_is_scaled_and_clipped() {
+    let mut framebuffer = Framebuffer::new(8, 8);
+    draw_text(&mut framebuffer, 4, 4, "Mm", 0xFF0000, 2);
+    assert!(!lit_pixels(&framebuffer, 0xFF0000).is_empty());
+
+    assert_eq!(text_width("ab\nabcd", 1), 4 * ADVANCE - 1);
+    assert_eq!(text_height("ab\nabcd", 3), (LINE_HEIGHT + GLYPH_HEIGHT) * 3);
+}
+
+#[test]
+fn hud_lists_the_frame_state() {
+    let camera = Camera::new(Vec3::new(1.0, 2.0, 3.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+    let info = HudInfo {
+        body: "Saturn",
+        fps: 29.5,
+        frame: FrameTimes::default(),
+        pipeline: RenderStats { triangles: 120, fragments: 900, shaded: 700, depth_rejected: 150, clipped: 50, ..Default::default() },
+        profiling: false,
+        time: 40.0,
+        clock_status: "x2".to_string(),
+        render_size: (340, 400),
+        resolution_status: "res 50%".to_string(),
+        camera: &camera,
+        effects: vec!["sun glow", "bilinear upscale"],
+    };
+
+    let text = hud_text(&info);
+    assert!(text.starts_with("Saturn\n29.5 fps"));
+    assert!(text.contains("time 40.0 s (2.00 days)  x2"));
+    assert!(text.contains("120 tris  900 frags  700 shaded  150 z-rejected"));
+    assert!(text.contains("res 50%  340x400"));
+    assert!(text.contains("camera 1.00 2.00 3.00"));
+    assert!(text.contains("effects: sun glow, bilinear upscale"));
+
+    let mut framebuffer = Framebuffer::new(400, 200);
+    framebuffer.set_background_color(0x808080);
+    framebuffer.clear();
+    draw_hud(&mut framebuffer, &info);
+    assert_eq!(framebuffer.buffer[0], 0x808080);
+    assert!(framebuffer.buffer.iter().any(|&pixel| pixel != 0x808080));
+}
@@ -0,0 +1,58 @@
+use nalgebra_glm::{Vec3, Vec4};
+use shaders::camera::Camera;
+use shaders::render::{create_projection_matrix, create_view_matrix};
+use shaders::transition::{CameraPose, CameraTransition, Easing};
+
+#[test]
+fn easing_curves_start_at_zero_and_end_at_one() {
+    for easing in Easing::ALL {
+        assert_eq!(easing.apply(0.0), 0.0, "{}", easing.name());
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{}", easing.name());
+        assert!(easing.apply(0.25) <= easing.apply(0.75), "{}", easing.name());
+        assert_eq!(Easing::from_name(easing.name()), Some(easing));
+    }
+}
+
+#[test]
+fn transition_ends_exactly_on_the_target_pose() {
+    let mut camera = Camera::default();
+    let from = CameraPose::of(&camera);
+    let to = CameraPose {
+        eye: Vec3::new(40.0, 5.0, -3.0),
+        center: Vec3::new(30.0, 0.0, 0.0),
+        up: Vec3::new(0.0, 1.0, 0.0),
+        fov: 0.5,
+    };
+    let mut transition = CameraTransition::new(from, to, 1.0, Easing::EaseInOutCubic);
+
+    assert!(!transition.update(0.5, &mut camera));
+    let halfway = CameraPose::of(&camera);
+    assert!(halfway.center.x > 0.0 && halfway.center.x < 30.0);
+    assert!((halfway.up.magnitude() - 1.0).abs() < 1e-5);
+
+    assert!(transition.update(0.6, &mut camera));
+    assert_eq!(CameraPose::of(&camera), to);
+}
+
+#[test]
+fn framing_fits_the_body_in_the_viewport() {
+    let (width, height) = (680.0, 800.0);
+    let camera = Camera::default();
+    let target = Vec3::new(10.0, -2.0, 4.0);
+    let radius = 1.5;
+    let pose = CameraPose::framing(&camera, target, radius, width / height, 0.6);
+
+    // El borde de la silueta, visto desde el ojo, cae al 60% de la mitad del ancho (el lado más corto)
+    let forward = (pose.center - pose.eye).normalize();
+    let distance = (pose.center - pose.eye).magnitude();
+    let right = forward.cross(&pose.up).normalize();
+    let tangent = radius * distance / (distance * distance - radius * radius).sqrt();
+    let edge = target + right * tangent;
+
+    let mut framed = Camera::default();
+    pose.apply(&mut framed);
+    let clip = create_projection_matrix(&framed, width, height, 0.1, 1000.0)
+        * create_view_matrix(pose.eye, pose.center, pose.up)
+        * Vec4::new(edge.x, edge.y, edge.z, 1.0);
+    assert!((clip.x / clip.w - 0.6).abs() < 1e-3, "ndc x {}", clip.x / clip.w);
+}